@@ -0,0 +1,298 @@
+use std::sync::Arc;
+
+use axum::{Router, extract::State, response::Result, routing::delete};
+use http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use utils::{errors::HttpAppErr, jwt::AuthUser};
+
+use crate::{
+    config::AuthConfig,
+    kafka::outbox::{EventOutbox, OutboxMessage, user_event_key},
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        services::{
+            management::KeycloakManagement, queries::user::UserQuery,
+            requests::delete_user::DeleteUserRequest,
+        },
+    },
+    tenant::resolve_tenant,
+};
+
+pub struct AccountState<TManager, TOutbox> {
+    pub manager: Arc<TManager>,
+    pub outbox: Arc<TOutbox>,
+    pub config: Arc<AuthConfig>,
+}
+
+impl<TManager, TOutbox> Clone for AccountState<TManager, TOutbox> {
+    fn clone(&self) -> Self {
+        AccountState {
+            manager: self.manager.clone(),
+            outbox: self.outbox.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+pub fn create_account_router<TManager, TOutbox>(
+    manager: Arc<TManager>,
+    outbox: Arc<TOutbox>,
+    config: Arc<AuthConfig>,
+) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+    TOutbox: EventOutbox + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/api/account", delete(delete_account::<TManager, TOutbox>))
+        .with_state(AccountState {
+            manager,
+            outbox,
+            config,
+        })
+}
+
+async fn delete_account<TManager, TOutbox>(
+    State(state): State<AccountState<TManager, TOutbox>>,
+    headers: HeaderMap,
+    user: AuthUser,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+    TOutbox: EventOutbox + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    let profile = state
+        .manager
+        .get_user_by_id(&UserQuery::new(realm_name, &user.id))
+        .await_err_as_failed_dependency()
+        .await?
+        .ok_or(HttpAppErr::new(StatusCode::NOT_FOUND, "user not found"))?;
+
+    let email = profile.email.unwrap_or(profile.username);
+    let event = OutboxMessage::new(
+        &state.config.kafka.user_deleted_topic,
+        &user_event_key(&tenant.id, &email),
+        &UserDeletedEvent {
+            tenant: tenant.id.clone(),
+            id: profile.id.clone(),
+            email,
+        },
+    )?;
+
+    // keycloak deletes first so consumers never drop profile rows of a user that still exists,
+    // the outbox is local and a failure to record is logged with the payload to replay it
+    state
+        .manager
+        .delete_user(&DeleteUserRequest::new(realm_name, &profile.id))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    state
+        .outbox
+        .enqueue(&event)
+        .await
+        .inspect_err(|err| {
+            log::error!(
+                "user {0} deleted but event {1} not recorded: {err}",
+                profile.id,
+                event.payload
+            )
+        })
+        .map_err(|err| HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+    log::info!("user {0} deleted, event recorded", profile.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+struct UserDeletedEvent {
+    pub tenant: String,
+    pub id: String,
+    pub email: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{Extension, body::Body, http::Request};
+    use http::{Method, StatusCode, header::AUTHORIZATION};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::{
+        kafka::{
+            outbox::{EventOutbox, FailingOutbox},
+            outbox_implementation::SqliteEventOutbox,
+        },
+        keycloak::{
+            mock::server::{MockKeycloak, TEST_REALM, test_realm_description},
+            services::{
+                management::KeycloakManagement, requests::create_user::CreateUserRequest,
+                seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+            },
+        },
+        register_account::create_registration_router,
+    };
+
+    use super::create_account_router;
+
+    async fn start_keycloak_with_user() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        manager
+            .create_user(&CreateUserRequest::new(
+                &TEST_REALM,
+                &"jane@example.com",
+                &"Secret123",
+            ))
+            .await
+            .unwrap();
+
+        keycloak
+    }
+
+    async fn delete_account<TOutbox>(keycloak: &MockKeycloak, outbox: Arc<TOutbox>) -> StatusCode
+    where
+        TOutbox: EventOutbox + Send + Sync + 'static,
+    {
+        let token = keycloak.sign_in("jane@example.com");
+
+        create_account_router(keycloak.create_manager(), outbox, keycloak.create_config())
+            .layer(Extension(keycloak.create_validator()))
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri("/api/account")
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn user_exists(keycloak: &MockKeycloak) -> bool {
+        keycloak.with_data(|data| {
+            data.realm(TEST_REALM)
+                .unwrap()
+                .user("jane@example.com")
+                .is_some()
+        })
+    }
+
+    #[tokio::test]
+    async fn deletes_user_and_records_event() {
+        let keycloak = start_keycloak_with_user().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let status = delete_account(&keycloak, outbox.clone()).await;
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(!user_exists(&keycloak));
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].topic, "user-deleted");
+        assert_eq!(pending[0].key, "default:jane@example.com");
+
+        let event: Value = serde_json::from_str(&pending[0].payload).unwrap();
+        assert_eq!(event["tenant"], "default");
+        assert_eq!(event["email"], "jane@example.com");
+    }
+
+    #[tokio::test]
+    async fn reports_event_that_cannot_be_recorded_after_deleting_user() {
+        let keycloak = start_keycloak_with_user().await;
+
+        let status = delete_account(&keycloak, Arc::new(FailingOutbox)).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!user_exists(&keycloak));
+    }
+
+    #[tokio::test]
+    async fn records_no_event_when_keycloak_delete_fails() {
+        let keycloak = start_keycloak_with_user().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+        keycloak.fail(Method::DELETE, "/users/", StatusCode::INTERNAL_SERVER_ERROR);
+
+        let status = delete_account(&keycloak, outbox.clone()).await;
+
+        assert_eq!(status, StatusCode::FAILED_DEPENDENCY);
+        assert!(user_exists(&keycloak));
+        assert!(outbox.unpublished().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn deletion_can_be_retried_after_keycloak_failure() {
+        let keycloak = start_keycloak_with_user().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+        keycloak.fail(Method::DELETE, "/users/", StatusCode::INTERNAL_SERVER_ERROR);
+
+        let status = delete_account(&keycloak, outbox.clone()).await;
+
+        assert_eq!(status, StatusCode::FAILED_DEPENDENCY);
+        assert!(user_exists(&keycloak));
+
+        let status = delete_account(&keycloak, outbox.clone()).await;
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(!user_exists(&keycloak));
+        assert_eq!(outbox.unpublished().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn deletes_user_registered_with_mixed_case_email() {
+        let keycloak = MockKeycloak::start().await;
+        DefaultKeycloakSeeding::new(keycloak.create_manager())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let response = create_registration_router(
+            keycloak.create_manager(),
+            outbox.clone(),
+            keycloak.create_config(),
+        )
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/customers")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"email":"Jane@Example.com","password":"Secret123"}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let status = delete_account(&keycloak, outbox.clone()).await;
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(!user_exists(&keycloak));
+
+        let pending = outbox.unpublished().await;
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].key, pending[1].key);
+
+        let created: Value = serde_json::from_str(&pending[0].payload).unwrap();
+        let deleted: Value = serde_json::from_str(&pending[1].payload).unwrap();
+        assert_eq!(created["email"], "jane@example.com");
+        assert_eq!(deleted["email"], created["email"]);
+    }
+}
@@ -0,0 +1,400 @@
+use std::{collections::HashMap, net::IpAddr, time::Duration};
+
+use utils::{
+    config::{ConfigReader, ServiceConfig},
+    readiness::ReadinessSettings,
+    tenant::default_tenant,
+};
+
+pub struct AuthConfig {
+    pub service_host: String,
+    pub outbox_path: String,
+    pub keycloak: KeycloakConfig,
+    pub tenants: Vec<TenantConfig>,
+    pub kafka: KafkaConfig,
+    pub accounts: Vec<AccountConfig>,
+    pub login_limits: LoginLimitConfig,
+    pub resend_limits: LoginLimitConfig,
+    pub reset_limits: LoginLimitConfig,
+    pub trusted_proxies: Vec<IpAddr>,
+    pub startup: ReadinessSettings,
+}
+
+pub struct KeycloakConfig {
+    pub host: String,
+    pub admin_login: String,
+    pub admin_password: String,
+    pub client: String,
+    pub public_client: bool,
+    pub client_secret: Option<String>,
+    pub customer_role: String,
+    pub vendor_role: String,
+    pub verify_email: bool,
+    pub smtp: Option<SmtpConfig>,
+}
+
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub from: String,
+}
+
+pub struct TenantConfig {
+    pub id: String,
+    pub realm: String,
+    pub realm_file: Option<String>,
+}
+
+pub struct KafkaConfig {
+    pub host: String,
+    pub customer_topic: String,
+    pub vendor_topic: String,
+    pub user_deleted_topic: String,
+}
+
+pub struct AccountConfig {
+    pub name: String,
+    pub path: String,
+    pub role: String,
+    pub topic: String,
+    pub profile_fields: Vec<String>,
+    // role a signed-in user must hold to upgrade into this account type, None disables upgrades
+    pub upgrade_from: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct LoginLimitConfig {
+    pub ip_attempts: u32,
+    pub login_attempts: u32,
+    pub window: Duration,
+    pub lockout: Option<LockoutConfig>,
+}
+
+#[derive(Clone)]
+pub struct LockoutConfig {
+    pub failures: u32,
+    pub duration: Duration,
+}
+
+impl ServiceConfig for AuthConfig {
+    fn read(reader: &mut ConfigReader) -> Self {
+        let public_client = reader.parsed_or("KEYCLOAK_PUBLIC_CLIENT", true);
+        let client_secret = match public_client {
+            true => reader.optional("KEYCLOAK_CLIENT_SECRET"),
+            false => Some(reader.required("KEYCLOAK_CLIENT_SECRET")),
+        };
+
+        let keycloak = KeycloakConfig {
+            host: reader.required("KEYCLOAK_HOST"),
+            admin_login: reader.required("KEYCLOAK_ADMIN_LOGIN"),
+            admin_password: reader.required("KEYCLOAK_ADMIN_PASSWORD"),
+            client: reader.required("KEYCLOAK_CLIENT"),
+            public_client,
+            client_secret,
+            customer_role: reader.required("KEYCLOAK_CUSTOMER_ROLE"),
+            vendor_role: reader.required("KEYCLOAK_VENDOR_ROLE"),
+            verify_email: reader.parsed_or("KEYCLOAK_VERIFY_EMAIL", false),
+            smtp: SmtpConfig::read(reader),
+        };
+
+        let mut tenants = vec![TenantConfig {
+            id: reader.optional("TENANT_ID").unwrap_or_else(default_tenant),
+            realm: reader.required("KEYCLOAK_REALM"),
+            realm_file: reader.optional("KEYCLOAK_REALM_FILE"),
+        }];
+
+        for id in list(reader.optional("TENANTS")) {
+            tenants.push(TenantConfig::read(reader, &id));
+        }
+
+        let kafka = KafkaConfig {
+            host: reader.required("KAFKA_HOST"),
+            customer_topic: reader.required("KAFKA_CUSTOMER_TOPIC"),
+            vendor_topic: reader.required("KAFKA_VENDOR_TOPIC"),
+            user_deleted_topic: reader.required("KAFKA_USER_DELETED_TOPIC"),
+        };
+
+        let mut vendor =
+            AccountConfig::read(reader, "vendor", &keycloak.vendor_role, &kafka.vendor_topic);
+        // customers upgrade into vendors by default, unless vendors collect profile fields
+        if vendor.upgrade_from.is_none() && vendor.profile_fields.is_empty() {
+            vendor.upgrade_from = Some(keycloak.customer_role.clone());
+        }
+
+        let mut accounts = vec![
+            AccountConfig::read(
+                reader,
+                "customer",
+                &keycloak.customer_role,
+                &kafka.customer_topic,
+            ),
+            vendor,
+        ];
+
+        for name in list(reader.optional("ACCOUNT_TYPES")) {
+            let key = name.to_uppercase();
+            let role = reader.required(&format!("ACCOUNT_{key}_ROLE"));
+            let topic = reader.required(&format!("ACCOUNT_{key}_TOPIC"));
+
+            accounts.push(AccountConfig::read(reader, &name, &role, &topic));
+        }
+
+        AuthConfig {
+            service_host: reader.required("SERVICE_HOST"),
+            outbox_path: reader.required("OUTBOX_PATH"),
+            keycloak,
+            tenants,
+            kafka,
+            accounts,
+            login_limits: LoginLimitConfig {
+                lockout: Some(LockoutConfig {
+                    failures: reader.parsed_or("LOGIN_LOCKOUT_FAILURES", 5),
+                    duration: Duration::from_secs(reader.parsed_or("LOGIN_LOCKOUT_SECS", 300)),
+                }),
+                ..LoginLimitConfig::read(
+                    reader,
+                    "LOGIN_LIMIT",
+                    "LOGIN",
+                    LoginLimitConfig::without_lockout(20, 10, Duration::from_secs(60)),
+                )
+            },
+            resend_limits: LoginLimitConfig::read(
+                reader,
+                "RESEND_LIMIT",
+                "EMAIL",
+                LoginLimitConfig::without_lockout(10, 3, Duration::from_secs(900)),
+            ),
+            reset_limits: LoginLimitConfig::read(
+                reader,
+                "RESET_LIMIT",
+                "EMAIL",
+                LoginLimitConfig::without_lockout(10, 3, Duration::from_secs(900)),
+            ),
+            trusted_proxies: reader.parsed_list("TRUSTED_PROXIES"),
+            startup: ReadinessSettings::read(reader),
+        }
+    }
+}
+
+impl AuthConfig {
+    pub fn default_tenant(&self) -> &TenantConfig {
+        &self.tenants[0]
+    }
+
+    pub fn tenant(&self, id: &str) -> Option<&TenantConfig> {
+        self.tenants.iter().find(|tenant| tenant.id == id)
+    }
+}
+
+impl KeycloakConfig {
+    pub fn client_params(&self) -> HashMap<&'static str, String> {
+        let mut params = HashMap::new();
+        params.insert("client_id", self.client.clone());
+
+        if let Some(secret) = self.client_secret.as_ref().filter(|_| !self.public_client) {
+            params.insert("client_secret", secret.clone());
+        }
+
+        params
+    }
+}
+
+impl SmtpConfig {
+    fn read(reader: &mut ConfigReader) -> Option<Self> {
+        let host = reader.optional("SMTP_HOST")?;
+
+        Some(SmtpConfig {
+            host,
+            port: reader.parsed_or("SMTP_PORT", 25),
+            from: reader.required("SMTP_FROM"),
+        })
+    }
+}
+
+impl TenantConfig {
+    pub fn new(id: &str, realm: &str) -> Self {
+        TenantConfig {
+            id: id.to_owned(),
+            realm: realm.to_owned(),
+            realm_file: None,
+        }
+    }
+
+    fn read(reader: &mut ConfigReader, id: &str) -> Self {
+        let key = id.to_uppercase().replace('-', "_");
+
+        TenantConfig {
+            id: id.to_owned(),
+            realm: reader.required(&format!("TENANT_{key}_REALM")),
+            realm_file: reader.optional(&format!("TENANT_{key}_REALM_FILE")),
+        }
+    }
+}
+
+impl LoginLimitConfig {
+    pub fn without_lockout(ip_attempts: u32, login_attempts: u32, window: Duration) -> Self {
+        LoginLimitConfig {
+            ip_attempts,
+            login_attempts,
+            window,
+            lockout: None,
+        }
+    }
+
+    fn read(reader: &mut ConfigReader, prefix: &str, login: &str, defaults: Self) -> Self {
+        let window_key = format!("{prefix}_WINDOW_SECS");
+        let window = Duration::from_secs(reader.parsed_or(&window_key, defaults.window.as_secs()));
+
+        // a zero window never counts an attempt twice, so nothing would ever be limited
+        if window.is_zero() {
+            reader.error(&format!("{window_key} must be greater than zero"));
+        }
+
+        LoginLimitConfig::without_lockout(
+            reader.parsed_or(&format!("{prefix}_IP_ATTEMPTS"), defaults.ip_attempts),
+            reader.parsed_or(
+                &format!("{prefix}_{login}_ATTEMPTS"),
+                defaults.login_attempts,
+            ),
+            window,
+        )
+    }
+}
+
+impl AccountConfig {
+    pub fn new(name: &str, role: &str, topic: &str) -> Self {
+        AccountConfig {
+            name: name.to_owned(),
+            path: format!("/api/{name}s"),
+            role: role.to_owned(),
+            topic: topic.to_owned(),
+            profile_fields: vec![],
+            upgrade_from: None,
+        }
+    }
+
+    fn read(reader: &mut ConfigReader, name: &str, role: &str, topic: &str) -> Self {
+        let key = name.to_uppercase();
+        let mut account = AccountConfig::new(name, role, topic);
+
+        if let Some(path) = reader.optional(&format!("ACCOUNT_{key}_PATH")) {
+            account.path = path;
+        }
+        account.profile_fields = list(reader.optional(&format!("ACCOUNT_{key}_PROFILE_FIELDS")));
+        account.upgrade_from = reader.optional(&format!("ACCOUNT_{key}_UPGRADE_FROM"));
+
+        // the upgrade request has no body, so it cannot supply the profile fields
+        if account.upgrade_from.is_some() && !account.profile_fields.is_empty() {
+            reader.error(&format!(
+                "ACCOUNT_{key}_UPGRADE_FROM cannot be combined with ACCOUNT_{key}_PROFILE_FIELDS"
+            ));
+        }
+
+        account
+    }
+}
+
+fn list(value: Option<String>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use utils::config::ConfigSource;
+
+    use super::AuthConfig;
+
+    fn source(overrides: &[(&str, &str)]) -> ConfigSource {
+        let values: HashMap<String, String> = [
+            ("SERVICE_HOST", "0.0.0.0:5001"),
+            ("OUTBOX_PATH", ":memory:"),
+            ("KEYCLOAK_HOST", "http://keycloak:8080"),
+            ("KEYCLOAK_ADMIN_LOGIN", "admin"),
+            ("KEYCLOAK_ADMIN_PASSWORD", "admin"),
+            ("KEYCLOAK_CLIENT", "app_client"),
+            ("KEYCLOAK_REALM", "store"),
+            ("KEYCLOAK_CUSTOMER_ROLE", "customer"),
+            ("KEYCLOAK_VENDOR_ROLE", "vendor"),
+            ("KAFKA_HOST", "kafka:9092"),
+            ("KAFKA_CUSTOMER_TOPIC", "customer-created"),
+            ("KAFKA_VENDOR_TOPIC", "vendor-created"),
+            ("KAFKA_USER_DELETED_TOPIC", "user-deleted"),
+        ]
+        .into_iter()
+        .chain(overrides.iter().copied())
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+
+        ConfigSource::new(values, HashMap::new())
+    }
+
+    fn config_error(overrides: &[(&str, &str)]) -> String {
+        source(overrides)
+            .read::<AuthConfig>()
+            .err()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn reads_login_and_resend_limits() {
+        let config = source(&[("RESEND_LIMIT_EMAIL_ATTEMPTS", "5")])
+            .read::<AuthConfig>()
+            .ok()
+            .unwrap();
+
+        let lockout = config.login_limits.lockout.unwrap();
+        assert_eq!(lockout.failures, 5);
+        assert_eq!(lockout.duration, Duration::from_secs(300));
+
+        assert!(config.resend_limits.lockout.is_none());
+        assert_eq!(config.resend_limits.login_attempts, 5);
+        assert_eq!(config.resend_limits.window, Duration::from_secs(900));
+    }
+
+    #[test]
+    fn rejects_zero_limit_windows() {
+        let err = config_error(&[
+            ("LOGIN_LIMIT_WINDOW_SECS", "0"),
+            ("RESEND_LIMIT_WINDOW_SECS", "0"),
+        ]);
+
+        assert!(err.contains("LOGIN_LIMIT_WINDOW_SECS must be greater than zero"));
+        assert!(err.contains("RESEND_LIMIT_WINDOW_SECS must be greater than zero"));
+    }
+
+    #[test]
+    fn rejects_upgrade_into_account_type_with_profile_fields() {
+        let err = config_error(&[
+            ("ACCOUNT_TYPES", "courier"),
+            ("ACCOUNT_COURIER_ROLE", "courier"),
+            ("ACCOUNT_COURIER_TOPIC", "courier-created"),
+            ("ACCOUNT_COURIER_PROFILE_FIELDS", "phone"),
+            ("ACCOUNT_COURIER_UPGRADE_FROM", "customer"),
+        ]);
+
+        assert!(err.contains(
+            "ACCOUNT_COURIER_UPGRADE_FROM cannot be combined with ACCOUNT_COURIER_PROFILE_FIELDS"
+        ));
+    }
+
+    #[test]
+    fn keeps_vendor_upgrade_only_without_profile_fields() {
+        let config = source(&[]).read::<AuthConfig>().ok().unwrap();
+        assert_eq!(config.accounts[1].upgrade_from.as_deref(), Some("customer"));
+
+        let config = source(&[("ACCOUNT_VENDOR_PROFILE_FIELDS", "company")])
+            .read::<AuthConfig>()
+            .ok()
+            .unwrap();
+        assert!(config.accounts[1].upgrade_from.is_none());
+    }
+}
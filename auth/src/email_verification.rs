@@ -0,0 +1,306 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    Router,
+    extract::{ConnectInfo, State},
+    response::Result,
+    routing::post,
+};
+use http::{HeaderMap, StatusCode};
+use serde::Deserialize;
+use utils::validation::{ValidJson, Validate, Validator};
+
+use crate::{
+    config::AuthConfig,
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        services::{
+            management::KeycloakManagement, queries::users::UsersQuery,
+            requests::execute_actions_email::ExecuteActionsEmailRequest,
+        },
+    },
+    rate_limit::{
+        client_ip::client_ip,
+        login_limiter::{LoginAttempt, LoginLimiter, enforce_limit},
+    },
+    tenant::resolve_tenant,
+};
+
+pub struct EmailVerificationState<TManager, TLimiter> {
+    pub manager: Arc<TManager>,
+    pub config: Arc<AuthConfig>,
+    pub limiter: Arc<TLimiter>,
+}
+
+impl<TManager, TLimiter> Clone for EmailVerificationState<TManager, TLimiter> {
+    fn clone(&self) -> Self {
+        EmailVerificationState {
+            manager: self.manager.clone(),
+            config: self.config.clone(),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+pub fn create_email_verification_router<TManager, TLimiter>(
+    manager: Arc<TManager>,
+    config: Arc<AuthConfig>,
+    limiter: Arc<TLimiter>,
+) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+    TLimiter: LoginLimiter + Send + Sync + 'static,
+{
+    if !config.keycloak.verify_email {
+        return Router::new();
+    }
+
+    Router::new()
+        .route(
+            "/api/email/resend",
+            post(resend_verification_email::<TManager, TLimiter>),
+        )
+        .with_state(EmailVerificationState {
+            manager,
+            config,
+            limiter,
+        })
+}
+
+async fn resend_verification_email<TManager, TLimiter>(
+    State(state): State<EmailVerificationState<TManager, TLimiter>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<ResendVerificationRequest>,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+    TLimiter: LoginLimiter + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    // every request counts, so the endpoint cannot be used to flood a mailbox or probe addresses
+    let attempt = LoginAttempt::new(
+        &client_ip(&headers, &peer, &state.config.trusted_proxies),
+        &format!("{0}:{1}", tenant.id, request.email),
+    );
+
+    enforce_limit(state.limiter.as_ref(), &attempt, "verification emails").await?;
+
+    let users = state
+        .manager
+        .query_users(&UsersQuery::new(realm_name, &request.email))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    let user = users
+        .into_iter()
+        .find(|user| user.username.eq_ignore_ascii_case(&request.email));
+
+    match user {
+        Some(user) if user.enabled && !user.email_verified => {
+            state
+                .manager
+                .execute_actions_email(&ExecuteActionsEmailRequest::new_verify_email(
+                    realm_name, &user.id,
+                ))
+                .await_err_as_failed_dependency()
+                .await?;
+
+            log::info!("verification email resent to user {0}", user.id);
+        }
+        _ => log::info!("verification email requested for unknown or verified user"),
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ResendVerificationRequest {
+    pub email: String,
+}
+
+impl Validate for ResendVerificationRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator
+            .required("email", &self.email)
+            .email("email", &self.email);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use axum::{Router, body::Body, extract::ConnectInfo, http::Request};
+    use http::{Method, StatusCode, header::RETRY_AFTER};
+    use tower::ServiceExt;
+
+    use crate::{
+        keycloak::{
+            mock::server::{MockKeycloak, TEST_REALM, test_realm_description},
+            services::{
+                management::KeycloakManagement, requests::create_user::CreateUserRequest,
+                seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+            },
+        },
+        rate_limit::login_limiter_implementation::InMemoryLoginLimiter,
+    };
+
+    use super::create_email_verification_router;
+
+    async fn start_keycloak_with_user(email_verified: bool) -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        manager
+            .create_user(&CreateUserRequest::new(
+                &TEST_REALM,
+                &"jane@example.com",
+                &"secret",
+            ))
+            .await
+            .unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            let user = realm.users.iter_mut().next().unwrap();
+            user.email = Some("jane@example.com".to_owned());
+            user.email_verified = email_verified;
+        });
+
+        keycloak
+    }
+
+    fn verification_router(keycloak: &MockKeycloak, verify_email: bool) -> Router {
+        let mut config = keycloak.create_config();
+        Arc::get_mut(&mut config).unwrap().keycloak.verify_email = verify_email;
+        let limiter = Arc::new(InMemoryLoginLimiter::new(config.resend_limits.clone()));
+
+        create_email_verification_router(keycloak.create_manager(), config, limiter)
+    }
+
+    fn resend_request(email: &str) -> Request<Body> {
+        resend_request_from(email, [10, 0, 0, 1])
+    }
+
+    fn resend_request_from(email: &str, ip: [u8; 4]) -> Request<Body> {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/email/resend")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(r#"{{"email":"{email}"}}"#)))
+            .unwrap();
+
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((ip, 50000))));
+        request
+    }
+
+    fn sent_actions(keycloak: &MockKeycloak) -> Vec<String> {
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            realm.user("jane@example.com").unwrap().sent_actions.clone()
+        })
+    }
+
+    #[tokio::test]
+    async fn resends_verification_email_to_unverified_user() {
+        let keycloak = start_keycloak_with_user(false).await;
+
+        let response = verification_router(&keycloak, true)
+            .oneshot(resend_request("jane@example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(sent_actions(&keycloak), ["VERIFY_EMAIL"]);
+    }
+
+    #[tokio::test]
+    async fn accepts_unknown_or_verified_email_without_sending() {
+        let keycloak = start_keycloak_with_user(true).await;
+        let router = verification_router(&keycloak, true);
+
+        for email in ["jane@example.com", "nobody@example.com"] {
+            let response = router.clone().oneshot(resend_request(email)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+
+        assert!(sent_actions(&keycloak).is_empty());
+    }
+
+    #[tokio::test]
+    async fn limits_resends_per_email() {
+        let keycloak = start_keycloak_with_user(false).await;
+        let router = verification_router(&keycloak, true);
+
+        for ip in [[10, 0, 0, 1], [10, 0, 0, 2]] {
+            let response = router
+                .clone()
+                .oneshot(resend_request_from("Jane@example.com", ip))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+
+        let response = router
+            .oneshot(resend_request_from("jane@example.com", [10, 0, 0, 3]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        assert_eq!(sent_actions(&keycloak), ["VERIFY_EMAIL", "VERIFY_EMAIL"]);
+    }
+
+    #[tokio::test]
+    async fn limits_resends_per_ip() {
+        let keycloak = start_keycloak_with_user(false).await;
+        let router = verification_router(&keycloak, true);
+
+        for n in 0..5 {
+            let email = format!("nobody{n}@example.com");
+            let response = router
+                .clone()
+                .oneshot(resend_request(&email))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+
+        let response = router
+            .clone()
+            .oneshot(resend_request("jane@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response = router
+            .oneshot(resend_request_from("jane@example.com", [10, 0, 0, 2]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(sent_actions(&keycloak), ["VERIFY_EMAIL"]);
+    }
+
+    #[tokio::test]
+    async fn resend_is_not_routed_when_verification_is_disabled() {
+        let keycloak = start_keycloak_with_user(false).await;
+
+        let response = verification_router(&keycloak, false)
+            .oneshot(resend_request("jane@example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -5,23 +5,38 @@ use rdkafka::{
     ClientConfig,
     producer::{FutureProducer, FutureRecord},
 };
-use serde::Serialize;
 use utils::errors::AppErr;
 
-pub async fn produce_event<T: Serialize>(
-    host: &str,
-    topic: &str,
-    message: T,
-) -> Result<(), AppErr> {
-    let producer: FutureProducer = ClientConfig::new()
+pub fn create_producer(host: &str) -> Result<FutureProducer, AppErr> {
+    ClientConfig::new()
         .set("bootstrap.servers", host)
+        .set("enable.idempotence", "true")
         .create()
-        .map_err(|err| AppErr::from_owned(format!("failed to create kafka client {err}")))?;
+        .map_err(|err| AppErr::from_owned(format!("failed to create kafka client {err}")))
+}
+
+pub trait EventPublisher {
+    fn publish(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+}
 
-    let payload = serde_json::to_string(&message)
-        .map_err(|err| AppErr::from_owned(format!("failed to serialize payload: {err}")))?;
+impl EventPublisher for FutureProducer {
+    async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<(), AppErr> {
+        produce_payload(self, topic, key, payload).await
+    }
+}
 
-    let record: FutureRecord<'_, String, String> = FutureRecord::to(topic).payload(&payload);
+pub async fn produce_payload(
+    producer: &FutureProducer,
+    topic: &str,
+    key: &str,
+    payload: &str,
+) -> Result<(), AppErr> {
+    let record: FutureRecord<'_, str, str> = FutureRecord::to(topic).key(key).payload(payload);
 
     let _delivery = producer
         .send(record, Duration::from_secs(1))
@@ -1 +1,4 @@
-pub mod kafka_producer;
\ No newline at end of file
+pub mod kafka_producer;
+pub mod outbox;
+pub mod outbox_implementation;
+pub mod outbox_relay;
\ No newline at end of file
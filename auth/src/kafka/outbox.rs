@@ -0,0 +1,83 @@
+use serde::Serialize;
+use utils::errors::AppErr;
+
+pub struct OutboxMessage {
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+}
+
+impl OutboxMessage {
+    pub fn new(topic: &str, key: &str, event: &impl Serialize) -> Result<Self, AppErr> {
+        let payload = serde_json::to_string(event)
+            .map_err(|err| AppErr::from_owned(format!("failed to serialize payload: {err}")))?;
+
+        Ok(OutboxMessage {
+            topic: topic.to_owned(),
+            key: key.to_owned(),
+            payload,
+        })
+    }
+}
+
+// events of one user share a key, so they are relayed in the order they were recorded
+pub fn user_event_key(tenant: &str, email: &str) -> String {
+    format!("{tenant}:{0}", email.to_lowercase())
+}
+
+pub struct PendingOutboxMessage {
+    pub id: i64,
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+    pub attempts: u32,
+}
+
+pub trait EventOutbox {
+    fn enqueue(&self, message: &OutboxMessage) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn fetch_pending(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<PendingOutboxMessage>, AppErr>> + Send;
+
+    fn mark_published(&self, id: i64) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn mark_failed(
+        &self,
+        id: i64,
+        error: &str,
+        retry_in_secs: u64,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn purge_published(
+        &self,
+        older_than_secs: u64,
+    ) -> impl Future<Output = Result<usize, AppErr>> + Send;
+}
+
+#[cfg(test)]
+pub struct FailingOutbox;
+
+#[cfg(test)]
+impl EventOutbox for FailingOutbox {
+    async fn enqueue(&self, _message: &OutboxMessage) -> Result<(), AppErr> {
+        Err(AppErr::from("outbox is unavailable"))
+    }
+
+    async fn fetch_pending(&self, _limit: usize) -> Result<Vec<PendingOutboxMessage>, AppErr> {
+        Ok(vec![])
+    }
+
+    async fn mark_published(&self, _id: i64) -> Result<(), AppErr> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, _id: i64, _error: &str, _retry_in_secs: u64) -> Result<(), AppErr> {
+        Ok(())
+    }
+
+    async fn purge_published(&self, _older_than_secs: u64) -> Result<usize, AppErr> {
+        Ok(0)
+    }
+}
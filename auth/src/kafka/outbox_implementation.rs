@@ -0,0 +1,367 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::TryFutureExt;
+use rusqlite::{Connection, params};
+use utils::errors::AppErr;
+
+use super::outbox::{EventOutbox, OutboxMessage, PendingOutboxMessage};
+
+pub struct SqliteEventOutbox {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteEventOutbox {
+    pub fn open(path: &str) -> Result<Self, AppErr> {
+        let connection = Connection::open(path)
+            .map_err(|err| AppErr::from_owned(format!("failed to open outbox {path}: {err}")))?;
+
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                PRAGMA synchronous = FULL;
+                CREATE TABLE IF NOT EXISTS outbox_events (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    topic TEXT NOT NULL,
+                    message_key TEXT NOT NULL,
+                    payload TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    attempts INTEGER NOT NULL DEFAULT 0,
+                    next_attempt_at INTEGER NOT NULL,
+                    last_error TEXT,
+                    published_at INTEGER
+                );
+                CREATE INDEX IF NOT EXISTS outbox_events_pending
+                    ON outbox_events (published_at, next_attempt_at);
+                CREATE INDEX IF NOT EXISTS outbox_events_key
+                    ON outbox_events (message_key, published_at, id);",
+            )
+            .map_err(|err| AppErr::from_owned(format!("failed to prepare outbox: {err}")))?;
+
+        Ok(SqliteEventOutbox {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    async fn with_connection<T, F>(&self, operation: F) -> Result<T, AppErr>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, rusqlite::Error> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let connection = connection
+                .lock()
+                .map_err(|_| AppErr::from("outbox connection is poisoned"))?;
+
+            operation(&connection)
+                .map_err(|err| AppErr::from_owned(format!("outbox query failed: {err}")))
+        })
+        .map_err(|err| AppErr::from_owned(format!("outbox task failed: {err}")))
+        .await?
+    }
+
+    // every unpublished message, whether it is due or held back by ordering
+    #[cfg(test)]
+    pub async fn unpublished(&self) -> Vec<PendingOutboxMessage> {
+        self.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT id, topic, message_key, payload, attempts FROM outbox_events
+                WHERE published_at IS NULL
+                ORDER BY id",
+            )?;
+
+            let rows = statement.query_map([], |row| {
+                Ok(PendingOutboxMessage {
+                    id: row.get(0)?,
+                    topic: row.get(1)?,
+                    key: row.get(2)?,
+                    payload: row.get(3)?,
+                    attempts: row.get(4)?,
+                })
+            })?;
+
+            rows.collect()
+        })
+        .await
+        .unwrap()
+    }
+
+    #[cfg(test)]
+    pub async fn make_due(&self) {
+        self.with_connection(|connection| {
+            connection.execute("UPDATE outbox_events SET next_attempt_at = 0", [])
+        })
+        .await
+        .unwrap();
+    }
+}
+
+impl EventOutbox for SqliteEventOutbox {
+    async fn enqueue(&self, message: &OutboxMessage) -> Result<(), AppErr> {
+        let topic = message.topic.clone();
+        let key = message.key.clone();
+        let payload = message.payload.clone();
+        let now = unix_now();
+
+        self.with_connection(move |connection| {
+            connection.execute(
+                "INSERT INTO outbox_events (topic, message_key, payload, created_at, next_attempt_at)
+                VALUES (?1, ?2, ?3, ?4, ?4)",
+                params![topic, key, payload, now],
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_pending(&self, limit: usize) -> Result<Vec<PendingOutboxMessage>, AppErr> {
+        let now = unix_now();
+        let limit = limit as i64;
+
+        self.with_connection(move |connection| {
+            // a message waits while an older message with the same key is unpublished,
+            // so a retried event never gets overtaken by the events recorded after it
+            let mut statement = connection.prepare(
+                "SELECT id, topic, message_key, payload, attempts FROM outbox_events AS pending
+                WHERE published_at IS NULL AND next_attempt_at <= ?1
+                AND NOT EXISTS (
+                    SELECT 1 FROM outbox_events AS earlier
+                    WHERE earlier.message_key = pending.message_key
+                    AND earlier.published_at IS NULL
+                    AND earlier.id < pending.id
+                )
+                ORDER BY id
+                LIMIT ?2",
+            )?;
+
+            let rows = statement.query_map(params![now, limit], |row| {
+                Ok(PendingOutboxMessage {
+                    id: row.get(0)?,
+                    topic: row.get(1)?,
+                    key: row.get(2)?,
+                    payload: row.get(3)?,
+                    attempts: row.get(4)?,
+                })
+            })?;
+
+            rows.collect()
+        })
+        .await
+    }
+
+    async fn mark_published(&self, id: i64) -> Result<(), AppErr> {
+        let now = unix_now();
+
+        self.with_connection(move |connection| {
+            connection.execute(
+                "UPDATE outbox_events SET published_at = ?1, last_error = NULL WHERE id = ?2",
+                params![now, id],
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, error: &str, retry_in_secs: u64) -> Result<(), AppErr> {
+        let error = error.to_owned();
+        let next_attempt_at = unix_now() + retry_in_secs as i64;
+
+        self.with_connection(move |connection| {
+            connection.execute(
+                "UPDATE outbox_events
+                SET attempts = attempts + 1, last_error = ?1, next_attempt_at = ?2
+                WHERE id = ?3",
+                params![error, next_attempt_at, id],
+            )
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn purge_published(&self, older_than_secs: u64) -> Result<usize, AppErr> {
+        let published_before = unix_now() - older_than_secs as i64;
+
+        self.with_connection(move |connection| {
+            connection.execute(
+                "DELETE FROM outbox_events
+                WHERE published_at IS NOT NULL AND published_at < ?1",
+                params![published_before],
+            )
+        })
+        .await
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::kafka::outbox::user_event_key;
+
+    fn message(topic: &str, email: &str) -> OutboxMessage {
+        OutboxMessage::new(
+            topic,
+            &user_event_key("default", email),
+            &json!({ "email": email }),
+        )
+        .unwrap()
+    }
+
+    fn topics(messages: &[PendingOutboxMessage]) -> Vec<(&str, &str)> {
+        messages
+            .iter()
+            .map(|x| (x.topic.as_str(), x.key.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fetches_enqueued_messages_in_order() {
+        let outbox = SqliteEventOutbox::open(":memory:").unwrap();
+        outbox
+            .enqueue(&message("customer-created", "Jane@Example.com"))
+            .await
+            .unwrap();
+        outbox
+            .enqueue(&message("vendor-created", "john@example.com"))
+            .await
+            .unwrap();
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+
+        assert_eq!(
+            topics(&pending),
+            vec![
+                ("customer-created", "default:jane@example.com"),
+                ("vendor-created", "default:john@example.com"),
+            ]
+        );
+        assert_eq!(pending[0].payload, r#"{"email":"Jane@Example.com"}"#);
+        assert_eq!(pending[0].attempts, 0);
+        assert_eq!(outbox.fetch_pending(1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn holds_back_messages_until_earlier_message_with_same_key_is_published() {
+        let outbox = SqliteEventOutbox::open(":memory:").unwrap();
+        outbox
+            .enqueue(&message("customer-created", "jane@example.com"))
+            .await
+            .unwrap();
+        outbox
+            .enqueue(&message("user-deleted", "jane@example.com"))
+            .await
+            .unwrap();
+        outbox
+            .enqueue(&message("customer-created", "john@example.com"))
+            .await
+            .unwrap();
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(
+            topics(&pending),
+            vec![
+                ("customer-created", "default:jane@example.com"),
+                ("customer-created", "default:john@example.com"),
+            ]
+        );
+
+        outbox
+            .mark_failed(pending[0].id, "broker unavailable", 0)
+            .await
+            .unwrap();
+        outbox.mark_published(pending[1].id).await.unwrap();
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(
+            topics(&pending),
+            vec![("customer-created", "default:jane@example.com")]
+        );
+        assert_eq!(pending[0].attempts, 1);
+
+        outbox.mark_published(pending[0].id).await.unwrap();
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(
+            topics(&pending),
+            vec![("user-deleted", "default:jane@example.com")]
+        );
+    }
+
+    #[tokio::test]
+    async fn delays_failed_messages() {
+        let outbox = SqliteEventOutbox::open(":memory:").unwrap();
+        outbox
+            .enqueue(&message("customer-created", "jane@example.com"))
+            .await
+            .unwrap();
+        let id = outbox.fetch_pending(10).await.unwrap()[0].id;
+
+        outbox
+            .mark_failed(id, "broker unavailable", 60)
+            .await
+            .unwrap();
+
+        assert!(outbox.fetch_pending(10).await.unwrap().is_empty());
+
+        outbox.make_due().await;
+
+        assert_eq!(outbox.fetch_pending(10).await.unwrap()[0].attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn purges_only_published_messages_past_retention() {
+        let outbox = SqliteEventOutbox::open(":memory:").unwrap();
+        outbox
+            .enqueue(&message("customer-created", "jane@example.com"))
+            .await
+            .unwrap();
+        outbox
+            .enqueue(&message("customer-created", "john@example.com"))
+            .await
+            .unwrap();
+        outbox
+            .enqueue(&message("customer-created", "jack@example.com"))
+            .await
+            .unwrap();
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        outbox.mark_published(pending[0].id).await.unwrap();
+        outbox.mark_published(pending[1].id).await.unwrap();
+
+        let old_id = pending[0].id;
+        outbox
+            .with_connection(move |connection| {
+                connection.execute(
+                    "UPDATE outbox_events SET published_at = 0 WHERE id = ?1",
+                    params![old_id],
+                )
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(outbox.purge_published(3600).await.unwrap(), 1);
+        assert_eq!(outbox.purge_published(3600).await.unwrap(), 0);
+
+        let remaining: i64 = outbox
+            .with_connection(|connection| {
+                connection.query_row("SELECT COUNT(*) FROM outbox_events", [], |row| row.get(0))
+            })
+            .await
+            .unwrap();
+        assert_eq!(remaining, 2);
+    }
+}
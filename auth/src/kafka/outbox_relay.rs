@@ -0,0 +1,266 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{select, time::Instant, time::sleep};
+use tokio_util::sync::CancellationToken;
+use utils::errors::AppErr;
+
+use super::{kafka_producer::EventPublisher, outbox::EventOutbox};
+
+const BATCH_SIZE: usize = 50;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY_SECS: u64 = 300;
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const PUBLISHED_RETENTION_SECS: u64 = 7 * 24 * 60 * 60;
+
+pub async fn run_outbox_relay<TOutbox, TPublisher>(
+    outbox: Arc<TOutbox>,
+    publisher: TPublisher,
+    cancellation_token: CancellationToken,
+) -> Result<(), AppErr>
+where
+    TOutbox: EventOutbox + Send + Sync,
+    TPublisher: EventPublisher + Send + Sync,
+{
+    log::info!("outbox relay started");
+
+    let mut next_purge = Instant::now();
+
+    loop {
+        if Instant::now() >= next_purge {
+            match outbox.purge_published(PUBLISHED_RETENTION_SECS).await {
+                Ok(purged) if purged > 0 => log::info!("purged {purged} published events"),
+                Ok(_) => {}
+                Err(err) => log::error!("failed to purge outbox: {err}"),
+            }
+            next_purge = Instant::now() + PURGE_INTERVAL;
+        }
+
+        let relayed = relay_pending(outbox.as_ref(), &publisher).await;
+
+        if relayed == BATCH_SIZE {
+            continue;
+        }
+
+        select! {
+            _ = sleep(POLL_INTERVAL) => {},
+            _ = cancellation_token.cancelled() => {
+                log::info!("outbox relay stopped");
+                return Ok(());
+            }
+        }
+    }
+}
+
+// publishes one batch of due events and returns how many were attempted
+pub async fn relay_pending<TOutbox, TPublisher>(outbox: &TOutbox, publisher: &TPublisher) -> usize
+where
+    TOutbox: EventOutbox + Send + Sync,
+    TPublisher: EventPublisher + Send + Sync,
+{
+    let pending = outbox
+        .fetch_pending(BATCH_SIZE)
+        .await
+        .inspect_err(|err| log::error!("failed to read outbox: {err}"))
+        .unwrap_or_default();
+
+    for message in pending.iter() {
+        match publisher
+            .publish(&message.topic, &message.key, &message.payload)
+            .await
+        {
+            Ok(()) => {
+                if let Err(err) = outbox.mark_published(message.id).await {
+                    log::error!("failed to mark event {0} as published: {err}", message.id);
+                }
+            }
+            Err(err) => {
+                let retry_in = retry_delay_secs(message.attempts);
+                log::warn!(
+                    "failed to publish event {0} to {1}, retry in {retry_in}s: {err}",
+                    message.id,
+                    message.topic
+                );
+
+                if let Err(err) = outbox
+                    .mark_failed(message.id, &err.to_string(), retry_in)
+                    .await
+                {
+                    log::error!("failed to reschedule event {0}: {err}", message.id);
+                }
+            }
+        }
+    }
+
+    pending.len()
+}
+
+fn retry_delay_secs(attempts: u32) -> u64 {
+    2u64.saturating_pow(attempts).min(MAX_RETRY_DELAY_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use serde_json::json;
+    use utils::errors::AppErr;
+
+    use super::*;
+    use crate::kafka::{
+        outbox::{OutboxMessage, user_event_key},
+        outbox_implementation::SqliteEventOutbox,
+    };
+
+    #[derive(Default)]
+    struct RecordingPublisher {
+        failing_topics: Mutex<Vec<String>>,
+        published: Mutex<Vec<(String, String, String)>>,
+    }
+
+    impl RecordingPublisher {
+        fn fail_topic(&self, topic: &str) {
+            self.failing_topics.lock().unwrap().push(topic.to_owned());
+        }
+
+        fn recover(&self) {
+            self.failing_topics.lock().unwrap().clear();
+        }
+
+        fn published(&self) -> Vec<(String, String, String)> {
+            self.published.lock().unwrap().clone()
+        }
+    }
+
+    impl EventPublisher for RecordingPublisher {
+        async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<(), AppErr> {
+            if self
+                .failing_topics
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|x| x == topic)
+            {
+                return Err(AppErr::from("broker unavailable"));
+            }
+
+            self.published.lock().unwrap().push((
+                topic.to_owned(),
+                key.to_owned(),
+                payload.to_owned(),
+            ));
+
+            Ok(())
+        }
+    }
+
+    async fn enqueue(outbox: &SqliteEventOutbox, topic: &str, email: &str) {
+        let message = OutboxMessage::new(
+            topic,
+            &user_event_key("default", email),
+            &json!({ "email": email }),
+        )
+        .unwrap();
+
+        outbox.enqueue(&message).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn publishes_pending_events_with_their_keys() {
+        let outbox = SqliteEventOutbox::open(":memory:").unwrap();
+        let publisher = RecordingPublisher::default();
+        enqueue(&outbox, "customer-created", "jane@example.com").await;
+        enqueue(&outbox, "vendor-created", "john@example.com").await;
+
+        let relayed = relay_pending(&outbox, &publisher).await;
+
+        assert_eq!(relayed, 2);
+        assert_eq!(
+            publisher.published(),
+            vec![
+                (
+                    "customer-created".to_owned(),
+                    "default:jane@example.com".to_owned(),
+                    r#"{"email":"jane@example.com"}"#.to_owned()
+                ),
+                (
+                    "vendor-created".to_owned(),
+                    "default:john@example.com".to_owned(),
+                    r#"{"email":"john@example.com"}"#.to_owned()
+                ),
+            ]
+        );
+        assert!(outbox.fetch_pending(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reschedules_failed_events_without_reordering_them() {
+        let outbox = SqliteEventOutbox::open(":memory:").unwrap();
+        let publisher = RecordingPublisher::default();
+        enqueue(&outbox, "customer-created", "jane@example.com").await;
+        enqueue(&outbox, "user-deleted", "jane@example.com").await;
+        enqueue(&outbox, "customer-created", "john@example.com").await;
+        publisher.fail_topic("customer-created");
+
+        let relayed = relay_pending(&outbox, &publisher).await;
+
+        assert_eq!(relayed, 2);
+        assert!(publisher.published().is_empty());
+        assert!(outbox.fetch_pending(10).await.unwrap().is_empty());
+
+        let failed = outbox.unpublished().await;
+        assert_eq!(failed[0].attempts, 1);
+        assert_eq!(failed[1].attempts, 0);
+        assert_eq!(failed[2].attempts, 1);
+
+        publisher.recover();
+        outbox.make_due().await;
+
+        relay_pending(&outbox, &publisher).await;
+        relay_pending(&outbox, &publisher).await;
+
+        let published: Vec<_> = publisher
+            .published()
+            .into_iter()
+            .map(|(topic, key, _)| (topic, key))
+            .collect();
+
+        assert_eq!(
+            published,
+            vec![
+                (
+                    "customer-created".to_owned(),
+                    "default:jane@example.com".to_owned()
+                ),
+                (
+                    "customer-created".to_owned(),
+                    "default:john@example.com".to_owned()
+                ),
+                (
+                    "user-deleted".to_owned(),
+                    "default:jane@example.com".to_owned()
+                ),
+            ]
+        );
+        assert!(outbox.unpublished().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_when_cancelled() {
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+
+        let result =
+            run_outbox_relay(outbox, RecordingPublisher::default(), cancellation_token).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn doubles_retry_delay_up_to_limit() {
+        assert_eq!(retry_delay_secs(0), 1);
+        assert_eq!(retry_delay_secs(3), 8);
+        assert_eq!(retry_delay_secs(20), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(u32::MAX), MAX_RETRY_DELAY_SECS);
+    }
+}
@@ -1,5 +1,6 @@
 use futures::TryFutureExt;
-use utils::errors::{AppErr, HttpAppErr};
+use http::StatusCode;
+use utils::errors::{AppErr, AppErrKind, HttpAppErr};
 
 pub trait KeycloakExtensions<T, Ok>
 where
@@ -15,7 +16,10 @@ where
 {
     async fn await_err_as_failed_dependency(self) -> Result<Ok, HttpAppErr> {
         self.inspect_err(|err| log::warn!("request failed with: {err}"))
-            .map_err(HttpAppErr::failed_dependency)
+            .map_err(|err| match err.kind() {
+                AppErrKind::Conflict => HttpAppErr::from(StatusCode::CONFLICT, err),
+                _ => HttpAppErr::failed_dependency(err),
+            })
             .await
     }
 
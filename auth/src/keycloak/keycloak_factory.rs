@@ -1,48 +1,32 @@
 use std::sync::Arc;
 
+use reqwest::Client;
+
+use crate::config::KeycloakConfig;
+
 use super::services::{
     authorization::AdminAccessTokenProvider,
     authorization_implementation::DefaultAdminTokenProvider,
-    credentials_implementation::EnvAdminCredentialProvider,
-    host_implementation::EnvHostAddressProvider,
+    credentials_implementation::ConfiguredAdminCredentialProvider,
+    host_implementation::ConfiguredHostAddressProvider,
     management::KeycloakManagement,
     management_implementation::DefaultKeycloakManagement,
     routes::Routes,
     routes_implementation::{DefaultAdminRoutes, DefaultRoutes},
 };
 
-pub fn create_default_manager() -> Arc<impl KeycloakManagement> {
-    let host_provider = Arc::new(EnvHostAddressProvider::new(&"KEYCLOAK_HOST"));
-
-    let credentials_provider = Arc::new(EnvAdminCredentialProvider::new(
-        &"KEYCLOAK_ADMIN_LOGIN",
-        &"KEYCLOAK_ADMIN_PASSWORD",
-    ));
-
-    let routes = Arc::new(DefaultAdminRoutes::new(host_provider));
-
-    let auth_provider = Arc::new(DefaultAdminTokenProvider::new(
-        routes.clone(),
-        credentials_provider,
-    ));
-
-    let manager = Arc::new(DefaultKeycloakManagement::new(
-        auth_provider,
-        routes.clone(),
-    ));
-
-    manager
-}
-
-pub fn create_default_manager_and_auth() -> (
-    Arc<impl KeycloakManagement>,
-    Arc<impl AdminAccessTokenProvider>,
+pub fn create_default_manager_and_auth(
+    config: &KeycloakConfig,
+    client: Arc<Client>,
+) -> (
+    Arc<impl KeycloakManagement + use<>>,
+    Arc<impl AdminAccessTokenProvider + use<>>,
 ) {
-    let host_provider = Arc::new(EnvHostAddressProvider::new(&"KEYCLOAK_HOST"));
+    let host_provider = Arc::new(ConfiguredHostAddressProvider::new(&config.host));
 
-    let credentials_provider = Arc::new(EnvAdminCredentialProvider::new(
-        &"KEYCLOAK_ADMIN_LOGIN",
-        &"KEYCLOAK_ADMIN_PASSWORD",
+    let credentials_provider = Arc::new(ConfiguredAdminCredentialProvider::new(
+        &config.admin_login,
+        &config.admin_password,
     ));
 
     let routes = Arc::new(DefaultAdminRoutes::new(host_provider));
@@ -50,18 +34,20 @@ pub fn create_default_manager_and_auth() -> (
     let auth_provider = Arc::new(DefaultAdminTokenProvider::new(
         routes.clone(),
         credentials_provider,
+        client.clone(),
     ));
 
     let manager = Arc::new(DefaultKeycloakManagement::new(
         auth_provider.clone(),
         routes.clone(),
+        client,
     ));
 
     (manager, auth_provider)
 }
 
-pub fn create_default_routes() -> Arc<impl Routes> {
-    let host_provider = Arc::new(EnvHostAddressProvider::new(&"KEYCLOAK_HOST"));
+pub fn create_default_routes(config: &KeycloakConfig) -> Arc<impl Routes + use<>> {
+    let host_provider = Arc::new(ConfiguredHostAddressProvider::new(&config.host));
     let routes = Arc::new(DefaultRoutes::new(host_provider));
 
     routes
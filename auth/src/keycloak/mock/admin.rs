@@ -0,0 +1,773 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use http::{StatusCode, header::LOCATION};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use super::state::{
+    MockClient, MockError, MockGroup, MockRealm, MockRole, MockState, MockUser, new_id,
+};
+
+type MockResult = Result<Response, MockError>;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmBody {
+    realm: Option<String>,
+    enabled: Option<bool>,
+    verify_email: Option<bool>,
+    smtp_server: Option<HashMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientBody {
+    client_id: String,
+    enabled: Option<bool>,
+    public_client: Option<bool>,
+    secret: Option<String>,
+    direct_access_grants_enabled: Option<bool>,
+    service_accounts_enabled: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct RoleBody {
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RoleReference {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserBody {
+    username: Option<String>,
+    enabled: Option<bool>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    attributes: Option<HashMap<String, Vec<String>>>,
+    required_actions: Option<Vec<String>>,
+    credentials: Option<Vec<CredentialBody>>,
+}
+
+#[derive(Deserialize)]
+pub struct CredentialBody {
+    value: String,
+}
+
+#[derive(Deserialize)]
+pub struct GroupBody {
+    name: String,
+}
+
+pub async fn create_realm(
+    State(state): State<MockState>,
+    Json(body): Json<RealmBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let name = body
+        .realm
+        .ok_or_else(|| MockError::new(StatusCode::BAD_REQUEST, "realm name is missing"))?;
+
+    if data.realm(&name).is_some() {
+        return Err(MockError::new(
+            StatusCode::CONFLICT,
+            "Conflict detected. See logs for details",
+        ));
+    }
+
+    let mut realm = MockRealm::new(&name, body.enabled.unwrap_or(false));
+    realm.verify_email = body.verify_email.unwrap_or(false);
+    realm.smtp_server = body.smtp_server.unwrap_or_default();
+    data.realms.push(realm);
+
+    Ok(created(&name))
+}
+
+pub async fn get_realm(Path(realm): Path<String>, State(state): State<MockState>) -> MockResult {
+    let data = state.lock();
+    let realm = data
+        .realm(&realm)
+        .ok_or_else(|| MockError::not_found("realm"))?;
+
+    Ok(Json(json!({
+        "realm": realm.name,
+        "enabled": realm.enabled,
+        "verifyEmail": realm.verify_email,
+        "smtpServer": realm.smtp_server,
+    }))
+    .into_response())
+}
+
+pub async fn update_realm(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Json(body): Json<RealmBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    if let Some(enabled) = body.enabled {
+        realm.enabled = enabled;
+    }
+    if let Some(verify_email) = body.verify_email {
+        realm.verify_email = verify_email;
+    }
+    if let Some(smtp_server) = body.smtp_server {
+        realm.smtp_server = smtp_server;
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn query_clients(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Query(query): Query<HashMap<String, String>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    let clients: Vec<Value> = realm
+        .clients
+        .iter()
+        .filter(|client| {
+            query
+                .get("clientId")
+                .is_none_or(|client_id| client.client_id == *client_id)
+        })
+        .map(client_json)
+        .collect();
+
+    Ok(Json(clients).into_response())
+}
+
+pub async fn create_client(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Json(body): Json<ClientBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    if realm.client(&body.client_id).is_some() {
+        return Err(MockError::new(
+            StatusCode::CONFLICT,
+            &format!("Client {0} already exists", body.client_id),
+        ));
+    }
+
+    let id = new_id("client");
+    realm.clients.push(MockClient {
+        id: id.clone(),
+        client_id: body.client_id,
+        enabled: body.enabled.unwrap_or(true),
+        public_client: body.public_client.unwrap_or(false),
+        secret: body.secret.unwrap_or_default(),
+        direct_access_grants_enabled: body.direct_access_grants_enabled.unwrap_or(false),
+        service_accounts_enabled: body.service_accounts_enabled.unwrap_or(false),
+        roles: vec![],
+    });
+
+    Ok(created(&id))
+}
+
+pub async fn update_client(
+    Path((realm, client)): Path<(String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<ClientBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let client = data.realm_mut(&realm)?.client_by_uuid_mut(&client)?;
+
+    client.client_id = body.client_id;
+    client.enabled = body.enabled.unwrap_or(client.enabled);
+    client.public_client = body.public_client.unwrap_or(client.public_client);
+    client.secret = body.secret.unwrap_or(client.secret.clone());
+    client.direct_access_grants_enabled = body
+        .direct_access_grants_enabled
+        .unwrap_or(client.direct_access_grants_enabled);
+    client.service_accounts_enabled = body
+        .service_accounts_enabled
+        .unwrap_or(client.service_accounts_enabled);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn query_client_roles(
+    Path((realm, client)): Path<(String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let client = data.realm_mut(&realm)?.client_by_uuid_mut(&client)?;
+
+    let roles: Vec<Value> = client.roles.iter().map(role_json).collect();
+    Ok(Json(roles).into_response())
+}
+
+pub async fn create_client_role(
+    Path((realm, client)): Path<(String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<RoleBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let client = data.realm_mut(&realm)?.client_by_uuid_mut(&client)?;
+
+    if client.roles.iter().any(|role| role.name == body.name) {
+        return Err(MockError::new(
+            StatusCode::CONFLICT,
+            &format!("Role with name {0} already exists", body.name),
+        ));
+    }
+
+    client.roles.push(MockRole {
+        id: new_id("role"),
+        name: body.name.clone(),
+        description: body.description,
+        composites: vec![],
+    });
+
+    Ok(created(&body.name))
+}
+
+pub async fn get_client_role(
+    Path((realm, client, role)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let client = data.realm_mut(&realm)?.client_by_uuid_mut(&client)?;
+
+    let role = client
+        .roles
+        .iter()
+        .find(|x| x.name == role)
+        .ok_or_else(|| MockError::not_found("role"))?;
+
+    Ok(Json(role_json(role)).into_response())
+}
+
+pub async fn update_client_role(
+    Path((realm, client, role)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<RoleBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let client = data.realm_mut(&realm)?.client_by_uuid_mut(&client)?;
+
+    let role = client
+        .roles
+        .iter_mut()
+        .find(|x| x.name == role)
+        .ok_or_else(|| MockError::not_found("role"))?;
+
+    role.name = body.name;
+    role.description = body.description;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn add_client_role_composites(
+    Path((realm, client, role)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<Vec<RoleReference>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+    let composites = resolve_role_ids(realm, &body)?;
+
+    let role = realm
+        .client_by_uuid_mut(&client)?
+        .roles
+        .iter_mut()
+        .find(|x| x.name == role)
+        .ok_or_else(|| MockError::not_found("role"))?;
+
+    for id in composites {
+        if !role.composites.contains(&id) {
+            role.composites.push(id);
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn add_realm_role_composites(
+    Path((realm, role)): Path<(String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<Vec<RoleReference>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    if role != format!("default-roles-{0}", realm.name.to_lowercase()) {
+        return Err(MockError::not_found("role"));
+    }
+
+    for id in resolve_role_ids(realm, &body)? {
+        if !realm.default_roles.contains(&id) {
+            realm.default_roles.push(id);
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn query_users(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Query(query): Query<HashMap<String, String>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+    let exact = query.get("exact").is_some_and(|x| x == "true");
+
+    let users: Vec<Value> = realm
+        .users
+        .iter()
+        .filter(|user| match query.get("username") {
+            Some(username) if exact => user.username.eq_ignore_ascii_case(username),
+            Some(username) => user
+                .username
+                .to_lowercase()
+                .contains(&username.to_lowercase()),
+            None => true,
+        })
+        .map(user_json)
+        .collect();
+
+    Ok(Json(users).into_response())
+}
+
+pub async fn create_user(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Json(body): Json<UserBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    let username = body
+        .username
+        .ok_or_else(|| MockError::new(StatusCode::BAD_REQUEST, "username is missing"))?;
+
+    if realm.user(&username).is_some() {
+        return Err(MockError::new(
+            StatusCode::CONFLICT,
+            "User exists with same username",
+        ));
+    }
+
+    ensure_email_is_free(realm, body.email.as_deref(), None)?;
+
+    let id = new_id("user");
+    realm.users.push(MockUser {
+        id: id.clone(),
+        username: username.to_lowercase(),
+        enabled: body.enabled.unwrap_or(false),
+        email: body.email.map(|email| email.to_lowercase()),
+        email_verified: body.email_verified.unwrap_or(false),
+        first_name: body.first_name,
+        last_name: body.last_name,
+        attributes: body.attributes.unwrap_or_default(),
+        required_actions: body.required_actions.unwrap_or_default(),
+        password: body
+            .credentials
+            .and_then(|credentials| credentials.into_iter().next())
+            .map(|credential| credential.value),
+        roles: vec![],
+        groups: vec![],
+        sent_actions: vec![],
+    });
+
+    Ok(created(&id))
+}
+
+pub async fn get_user(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let user = data.realm_mut(&realm)?.user_by_uuid_mut(&user)?;
+
+    Ok(Json(user_json(user)).into_response())
+}
+
+pub async fn update_user(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<UserBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    ensure_email_is_free(realm, body.email.as_deref(), Some(&user))?;
+
+    let user = realm.user_by_uuid_mut(&user)?;
+
+    if let Some(enabled) = body.enabled {
+        user.enabled = enabled;
+    }
+    if let Some(email) = body.email {
+        user.email = Some(email.to_lowercase());
+    }
+    if let Some(email_verified) = body.email_verified {
+        user.email_verified = email_verified;
+    }
+    if let Some(first_name) = body.first_name {
+        user.first_name = Some(first_name);
+    }
+    if let Some(last_name) = body.last_name {
+        user.last_name = Some(last_name);
+    }
+    if let Some(required_actions) = body.required_actions {
+        user.required_actions = required_actions;
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn delete_user(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    realm.user_by_uuid_mut(&user)?;
+    realm.users.retain(|x| x.id != user);
+    realm.sessions.retain(|_, user_id| *user_id != user);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn reset_password(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<CredentialBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let user = data.realm_mut(&realm)?.user_by_uuid_mut(&user)?;
+
+    user.password = Some(body.value);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn execute_actions_email(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+    Json(actions): Json<Vec<String>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let user = data.realm_mut(&realm)?.user_by_uuid_mut(&user)?;
+
+    if user.email.is_none() {
+        return Err(MockError::new(
+            StatusCode::BAD_REQUEST,
+            "User email missing",
+        ));
+    }
+
+    user.sent_actions.extend(actions);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn add_user_client_roles(
+    Path((realm, user, client)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<Vec<RoleReference>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+    let roles = resolve_client_role_ids(realm, &client, &body)?;
+
+    let user = realm.user_by_uuid_mut(&user)?;
+    for id in roles {
+        if !user.roles.contains(&id) {
+            user.roles.push(id);
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn remove_user_client_roles(
+    Path((realm, user, client)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<Vec<RoleReference>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+    let roles = resolve_client_role_ids(realm, &client, &body)?;
+
+    let user = realm.user_by_uuid_mut(&user)?;
+    user.roles.retain(|id| !roles.contains(id));
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn query_user_client_roles(
+    Path((realm, user, client)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    let user = realm
+        .users
+        .iter()
+        .find(|x| x.id == user)
+        .ok_or_else(|| MockError::not_found("user"))?;
+
+    let roles: Vec<Value> = realm
+        .user_client_roles(user, &client)
+        .into_iter()
+        .map(role_json)
+        .collect();
+
+    Ok(Json(roles).into_response())
+}
+
+pub async fn query_user_sessions(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    let username = realm.user_by_uuid_mut(&user)?.username.clone();
+
+    let sessions: Vec<Value> = realm
+        .sessions
+        .iter()
+        .filter(|(_, user_id)| **user_id == user)
+        .map(|(id, user_id)| {
+            json!({
+                "id": id,
+                "username": username,
+                "userId": user_id,
+                "ipAddress": "127.0.0.1",
+                "start": 0,
+                "lastAccess": 0,
+                "clients": {},
+            })
+        })
+        .collect();
+
+    Ok(Json(sessions).into_response())
+}
+
+pub async fn logout_user(
+    Path((realm, user)): Path<(String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    realm.user_by_uuid_mut(&user)?;
+    realm.sessions.retain(|_, user_id| *user_id != user);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn add_user_to_group(
+    Path((realm, user, group)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    if !realm.groups.iter().any(|x| x.id == group) {
+        return Err(MockError::not_found("group"));
+    }
+
+    let user = realm.user_by_uuid_mut(&user)?;
+    if !user.groups.contains(&group) {
+        user.groups.push(group);
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn query_groups(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Query(query): Query<HashMap<String, String>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+    let exact = query.get("exact").is_some_and(|x| x == "true");
+
+    let groups: Vec<Value> = realm
+        .groups
+        .iter()
+        .filter(|group| match query.get("search") {
+            Some(search) if exact => group.name == *search,
+            Some(search) => group.name.contains(search.as_str()),
+            None => true,
+        })
+        .map(|group| json!({ "id": group.id, "name": group.name }))
+        .collect();
+
+    Ok(Json(groups).into_response())
+}
+
+pub async fn create_group(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Json(body): Json<GroupBody>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+
+    if realm.group(&body.name).is_some() {
+        return Err(MockError::new(
+            StatusCode::CONFLICT,
+            &format!("Top level group named '{0}' already exists.", body.name),
+        ));
+    }
+
+    let id = new_id("group");
+    realm.groups.push(MockGroup {
+        id: id.clone(),
+        name: body.name,
+        roles: vec![],
+    });
+
+    Ok(created(&id))
+}
+
+pub async fn add_group_client_roles(
+    Path((realm, group, client)): Path<(String, String, String)>,
+    State(state): State<MockState>,
+    Json(body): Json<Vec<RoleReference>>,
+) -> MockResult {
+    let mut data = state.lock();
+    let realm = data.realm_mut(&realm)?;
+    let roles = resolve_client_role_ids(realm, &client, &body)?;
+
+    let group = realm
+        .groups
+        .iter_mut()
+        .find(|x| x.id == group)
+        .ok_or_else(|| MockError::not_found("group"))?;
+
+    for id in roles {
+        if !group.roles.contains(&id) {
+            group.roles.push(id);
+        }
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+fn resolve_role_ids(
+    realm: &MockRealm,
+    references: &[RoleReference],
+) -> Result<Vec<String>, MockError> {
+    references
+        .iter()
+        .map(|reference| {
+            realm
+                .role(&reference.id)
+                .map(|role| role.id.clone())
+                .ok_or_else(|| MockError::not_found("role"))
+        })
+        .collect()
+}
+
+fn resolve_client_role_ids(
+    realm: &mut MockRealm,
+    client: &str,
+    references: &[RoleReference],
+) -> Result<Vec<String>, MockError> {
+    let client = realm.client_by_uuid_mut(client)?;
+
+    references
+        .iter()
+        .map(|reference| {
+            client
+                .roles
+                .iter()
+                .find(|role| role.id == reference.id)
+                .map(|role| role.id.clone())
+                .ok_or_else(|| MockError::not_found("role"))
+        })
+        .collect()
+}
+
+fn ensure_email_is_free(
+    realm: &MockRealm,
+    email: Option<&str>,
+    owner: Option<&str>,
+) -> Result<(), MockError> {
+    let taken = email.is_some_and(|email| {
+        realm.users.iter().any(|user| {
+            Some(user.id.as_str()) != owner
+                && user
+                    .email
+                    .as_deref()
+                    .is_some_and(|x| x.eq_ignore_ascii_case(email))
+        })
+    });
+
+    if taken {
+        Err(MockError::new(
+            StatusCode::CONFLICT,
+            "User exists with same email",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn created(id: &str) -> Response {
+    (StatusCode::CREATED, [(LOCATION, id.to_owned())]).into_response()
+}
+
+fn client_json(client: &MockClient) -> Value {
+    json!({
+        "id": client.id,
+        "clientId": client.client_id,
+        "enabled": client.enabled,
+        "publicClient": client.public_client,
+        "secret": client.secret,
+        "directAccessGrantsEnabled": client.direct_access_grants_enabled,
+        "serviceAccountsEnabled": client.service_accounts_enabled,
+    })
+}
+
+fn role_json(role: &MockRole) -> Value {
+    json!({
+        "id": role.id,
+        "name": role.name,
+        "description": role.description,
+        "composite": !role.composites.is_empty(),
+        "clientRole": true,
+    })
+}
+
+fn user_json(user: &MockUser) -> Value {
+    json!({
+        "id": user.id,
+        "username": user.username,
+        "enabled": user.enabled,
+        "email": user.email,
+        "emailVerified": user.email_verified,
+        "firstName": user.first_name,
+        "lastName": user.last_name,
+        "attributes": user.attributes,
+        "requiredActions": user.required_actions,
+    })
+}
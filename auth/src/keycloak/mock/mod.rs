@@ -0,0 +1,5 @@
+pub mod admin;
+pub mod openid;
+pub mod server;
+pub mod signing;
+pub mod state;
\ No newline at end of file
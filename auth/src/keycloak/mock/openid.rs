@@ -0,0 +1,257 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    Form, Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use serde_json::json;
+
+use super::{
+    signing,
+    state::{MockData, MockRealm, MockState, new_id},
+};
+
+pub async fn token(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Response {
+    let mut data = state.lock();
+    let field = |name: &str| form.get(name).map(String::as_str).unwrap_or_default();
+
+    if realm == "master" && field("client_id") == "admin-cli" {
+        return admin_token(&mut data, &form);
+    }
+
+    let lifetime = data.token_lifetime;
+    let host = data.host.clone();
+    let Some(realm) = data.realms.iter_mut().find(|x| x.name == realm) else {
+        return oauth_error(
+            StatusCode::NOT_FOUND,
+            "invalid_request",
+            "Realm does not exist",
+        );
+    };
+
+    let Some(client) = realm.client(field("client_id")) else {
+        return oauth_error(StatusCode::UNAUTHORIZED, "invalid_client", "Invalid client");
+    };
+
+    if !client.enabled {
+        return oauth_error(
+            StatusCode::UNAUTHORIZED,
+            "invalid_client",
+            "Client disabled",
+        );
+    }
+
+    if !client.public_client && client.secret != field("client_secret") {
+        return oauth_error(
+            StatusCode::UNAUTHORIZED,
+            "unauthorized_client",
+            "Invalid client or Invalid client credentials",
+        );
+    }
+
+    if field("grant_type") == "client_credentials" {
+        if client.public_client || !client.service_accounts_enabled {
+            return oauth_error(
+                StatusCode::UNAUTHORIZED,
+                "unauthorized_client",
+                "Client not enabled to retrieve service account",
+            );
+        }
+
+        return Json(json!({
+            "access_token": new_id("service-access-token"),
+            "expires_in": lifetime,
+            "refresh_expires_in": 0,
+            "token_type": "Bearer",
+        }))
+        .into_response();
+    }
+
+    let user_id = match field("grant_type") {
+        "password" if !client.direct_access_grants_enabled => {
+            return oauth_error(
+                StatusCode::BAD_REQUEST,
+                "unauthorized_client",
+                "Client not allowed for direct access grants",
+            );
+        }
+        "password" => {
+            let user = realm
+                .user(field("username"))
+                .filter(|user| user.enabled)
+                .filter(|user| user.password.as_deref() == Some(field("password")));
+
+            match user {
+                Some(user) if !user.required_actions.is_empty() => {
+                    return oauth_error(
+                        StatusCode::BAD_REQUEST,
+                        "invalid_grant",
+                        "Account is not fully set up",
+                    );
+                }
+                Some(user) => user.id.clone(),
+                None => {
+                    return oauth_error(
+                        StatusCode::UNAUTHORIZED,
+                        "invalid_grant",
+                        "Invalid user credentials",
+                    );
+                }
+            }
+        }
+        "refresh_token" => match realm.sessions.remove(field("refresh_token")) {
+            Some(user_id) => user_id,
+            None => {
+                return oauth_error(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_grant",
+                    "Invalid refresh token",
+                );
+            }
+        },
+        _ => {
+            return oauth_error(
+                StatusCode::BAD_REQUEST,
+                "unsupported_grant_type",
+                "Unsupported grant_type",
+            );
+        }
+    };
+
+    let client_id = field("client_id").to_owned();
+    let refresh_token = new_id("refresh-token");
+    realm
+        .sessions
+        .insert(refresh_token.clone(), user_id.clone());
+
+    let access_token =
+        user_access_token(&host, realm, &client_id, &user_id, &refresh_token, lifetime);
+    token_response(&access_token, &refresh_token, lifetime)
+}
+
+pub async fn certs() -> Response {
+    Json(signing::jwks()).into_response()
+}
+
+pub fn user_access_token(
+    host: &str,
+    realm: &MockRealm,
+    client_id: &str,
+    user_id: &str,
+    session_id: &str,
+    lifetime: u64,
+) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default();
+
+    let user = realm.users.iter().find(|user| user.id == user_id);
+    let roles: Vec<&str> = match (user, realm.client(client_id)) {
+        (Some(user), Some(client)) => realm
+            .user_client_roles(user, &client.id)
+            .into_iter()
+            .map(|role| role.name.as_str())
+            .collect(),
+        _ => vec![],
+    };
+
+    signing::sign(&json!({
+        "iss": format!("{host}/realms/{0}", realm.name),
+        "sub": user_id,
+        "azp": client_id,
+        "sid": session_id,
+        "iat": now,
+        "exp": now + lifetime,
+        "preferred_username": user.map(|user| user.username.as_str()),
+        "email": user.and_then(|user| user.email.as_deref()),
+        "resource_access": { client_id: { "roles": roles } },
+    }))
+}
+
+pub async fn logout(
+    Path(realm): Path<String>,
+    State(state): State<MockState>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Response {
+    let mut data = state.lock();
+
+    let Some(realm) = data.realms.iter_mut().find(|x| x.name == realm) else {
+        return oauth_error(
+            StatusCode::NOT_FOUND,
+            "invalid_request",
+            "Realm does not exist",
+        );
+    };
+
+    let refresh_token = form
+        .get("refresh_token")
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    match realm.sessions.remove(refresh_token) {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => oauth_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_grant",
+            "Invalid refresh token",
+        ),
+    }
+}
+
+fn admin_token(data: &mut MockData, form: &HashMap<String, String>) -> Response {
+    let field = |name: &str| form.get(name).map(String::as_str).unwrap_or_default();
+    data.admin_grants.push(field("grant_type").to_owned());
+
+    let authorized = match field("grant_type") {
+        "password" => {
+            field("username") == data.admin_login && field("password") == data.admin_password
+        }
+        "refresh_token" => data.admin_refresh_tokens.remove(field("refresh_token")),
+        _ => false,
+    };
+
+    if !authorized {
+        return oauth_error(
+            StatusCode::UNAUTHORIZED,
+            "invalid_grant",
+            "Invalid user credentials",
+        );
+    }
+
+    let access_token = new_id("admin-access-token");
+    let refresh_token = new_id("admin-refresh-token");
+
+    data.admin_tokens.insert(access_token.clone());
+    data.admin_refresh_tokens.insert(refresh_token.clone());
+
+    token_response(&access_token, &refresh_token, data.token_lifetime)
+}
+
+fn token_response(access_token: &str, refresh_token: &str, lifetime: u64) -> Response {
+    Json(json!({
+        "access_token": access_token,
+        "expires_in": lifetime,
+        "refresh_token": refresh_token,
+        "refresh_expires_in": lifetime * 6,
+        "token_type": "Bearer",
+    }))
+    .into_response()
+}
+
+fn oauth_error(status: StatusCode, error: &str, description: &str) -> Response {
+    (
+        status,
+        Json(json!({ "error": error, "error_description": description })),
+    )
+        .into_response()
+}
@@ -0,0 +1,394 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    Router,
+    extract::{Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+};
+use http::{Method, StatusCode, header::AUTHORIZATION};
+use reqwest::Client;
+use tokio::{net::TcpListener, task::JoinHandle};
+use utils::{
+    http::{HttpClientSettings, create_http_client},
+    jwt::{JwtValidator, JwtValidatorSettings},
+    readiness::ReadinessSettings,
+    tenant::DEFAULT_TENANT,
+};
+
+use crate::{
+    config::{
+        AccountConfig, AuthConfig, KafkaConfig, KeycloakConfig, LockoutConfig, LoginLimitConfig,
+        TenantConfig,
+    },
+    keycloak::services::{
+        authorization_implementation::DefaultAdminTokenProvider,
+        credentials_implementation::ConfiguredAdminCredentialProvider,
+        host_implementation::ConfiguredHostAddressProvider,
+        management_implementation::DefaultKeycloakManagement, realm_description::RealmDescription,
+        routes_implementation::DefaultAdminRoutes, seeding::KeycloakSeedingArguments,
+    },
+};
+
+use super::{
+    admin, openid,
+    state::{InjectedFailure, MockData, MockError, MockState, new_id},
+};
+
+pub const TEST_REALM: &str = "test_realm";
+pub const TEST_CLIENT: &str = "app_client";
+pub const TEST_CUSTOMER_ROLE: &str = "customer";
+pub const TEST_VENDOR_ROLE: &str = "vendor";
+
+/// Builds the auth config the tests run with; only the keycloak host and admin credentials vary.
+pub fn create_test_config(
+    keycloak_host: &str,
+    admin_login: &str,
+    admin_password: &str,
+) -> AuthConfig {
+    AuthConfig {
+        service_host: "127.0.0.1:0".to_owned(),
+        outbox_path: ":memory:".to_owned(),
+        keycloak: KeycloakConfig {
+            host: keycloak_host.to_owned(),
+            admin_login: admin_login.to_owned(),
+            admin_password: admin_password.to_owned(),
+            client: TEST_CLIENT.to_owned(),
+            public_client: true,
+            client_secret: None,
+            customer_role: TEST_CUSTOMER_ROLE.to_owned(),
+            vendor_role: TEST_VENDOR_ROLE.to_owned(),
+            verify_email: false,
+            smtp: None,
+        },
+        tenants: vec![TenantConfig::new(DEFAULT_TENANT, TEST_REALM)],
+        kafka: KafkaConfig {
+            host: "127.0.0.1:9092".to_owned(),
+            customer_topic: "customer-created".to_owned(),
+            vendor_topic: "vendor-created".to_owned(),
+            user_deleted_topic: "user-deleted".to_owned(),
+        },
+        accounts: vec![
+            AccountConfig::new("customer", TEST_CUSTOMER_ROLE, "customer-created"),
+            AccountConfig {
+                upgrade_from: Some(TEST_CUSTOMER_ROLE.to_owned()),
+                ..AccountConfig::new("vendor", TEST_VENDOR_ROLE, "vendor-created")
+            },
+        ],
+        login_limits: LoginLimitConfig {
+            lockout: Some(LockoutConfig {
+                failures: 3,
+                duration: Duration::from_secs(300),
+            }),
+            ..LoginLimitConfig::without_lockout(20, 10, Duration::from_secs(60))
+        },
+        resend_limits: LoginLimitConfig::without_lockout(5, 2, Duration::from_secs(900)),
+        reset_limits: LoginLimitConfig::without_lockout(5, 2, Duration::from_secs(900)),
+        trusted_proxies: vec![],
+        startup: ReadinessSettings::default(),
+    }
+}
+
+pub type MockKeycloakManagement = DefaultKeycloakManagement<
+    DefaultAdminTokenProvider<
+        DefaultAdminRoutes<ConfiguredHostAddressProvider>,
+        ConfiguredAdminCredentialProvider,
+    >,
+    DefaultAdminRoutes<ConfiguredHostAddressProvider>,
+>;
+
+pub struct MockKeycloak {
+    host: String,
+    state: MockState,
+    server: JoinHandle<()>,
+}
+
+impl MockKeycloak {
+    pub async fn start() -> Self {
+        let state = MockState::default();
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("mock keycloak cannot bind");
+        let address = listener.local_addr().expect("mock keycloak has no address");
+        let host = format!("http://{address}");
+        state.lock().host = host.clone();
+
+        let app = create_mock_router(state.clone());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("mock keycloak failed");
+        });
+
+        MockKeycloak {
+            host,
+            state,
+            server,
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn create_config(&self) -> Arc<AuthConfig> {
+        let (admin_login, admin_password) =
+            self.with_data(|data| (data.admin_login.clone(), data.admin_password.clone()));
+
+        Arc::new(create_test_config(
+            &self.host,
+            &admin_login,
+            &admin_password,
+        ))
+    }
+
+    pub fn create_client(&self) -> Arc<Client> {
+        Arc::new(
+            create_http_client(&HttpClientSettings::new("mock-keycloak-tests"))
+                .expect("test http client cannot be built"),
+        )
+    }
+
+    pub fn create_manager(&self) -> Arc<MockKeycloakManagement> {
+        let host_provider = Arc::new(ConfiguredHostAddressProvider::new(&self.host));
+
+        let credentials_provider = Arc::new(self.with_data(|data| {
+            ConfiguredAdminCredentialProvider::new(&data.admin_login, &data.admin_password)
+        }));
+
+        let client = self.create_client();
+        let routes = Arc::new(DefaultAdminRoutes::new(host_provider));
+        let auth_provider = Arc::new(DefaultAdminTokenProvider::new(
+            routes.clone(),
+            credentials_provider,
+            client.clone(),
+        ));
+
+        Arc::new(DefaultKeycloakManagement::new(
+            auth_provider,
+            routes,
+            client,
+        ))
+    }
+
+    pub fn create_validator(&self) -> Arc<JwtValidator> {
+        Arc::new(JwtValidator::new(
+            JwtValidatorSettings {
+                host: self.host.clone(),
+                realms: vec![TEST_REALM.to_owned()],
+                client: TEST_CLIENT.to_owned(),
+            },
+            self.create_client(),
+        ))
+    }
+
+    pub fn sign_in(&self, username: &str) -> String {
+        self.with_data(|data| {
+            let (host, lifetime) = (data.host.clone(), data.token_lifetime);
+            let realm = data
+                .realm_mut(TEST_REALM)
+                .ok()
+                .expect("test realm is not seeded");
+            let user_id = realm
+                .user(username)
+                .expect("user does not exist")
+                .id
+                .clone();
+
+            let session_id = new_id("refresh-token");
+            realm.sessions.insert(session_id.clone(), user_id.clone());
+
+            openid::user_access_token(&host, realm, TEST_CLIENT, &user_id, &session_id, lifetime)
+        })
+    }
+
+    pub fn fail(&self, method: Method, path: &str, status: StatusCode) {
+        self.fail_times(method, path, status, 1);
+    }
+
+    pub fn fail_times(&self, method: Method, path: &str, status: StatusCode, times: usize) {
+        self.with_data(|data| {
+            data.failures.push(InjectedFailure {
+                method,
+                path: path.to_owned(),
+                status,
+                remaining: times,
+            })
+        });
+    }
+
+    pub fn request_count(&self, method: Method, path: &str) -> usize {
+        self.with_data(|data| {
+            data.requests
+                .iter()
+                .filter(|(x, y)| *x == method && y.contains(path))
+                .count()
+        })
+    }
+
+    pub fn with_data<T>(&self, operation: impl FnOnce(&mut MockData) -> T) -> T {
+        operation(&mut self.state.lock())
+    }
+}
+
+impl Drop for MockKeycloak {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+pub fn test_realm_description() -> RealmDescription {
+    RealmDescription::from(KeycloakSeedingArguments::new(
+        TEST_REALM,
+        TEST_CLIENT,
+        "",
+        TEST_CUSTOMER_ROLE,
+        "store customer",
+        TEST_VENDOR_ROLE,
+        "store vendor",
+    ))
+}
+
+fn create_mock_router(state: MockState) -> Router {
+    let admin = Router::new()
+        .route("/admin/realms", post(admin::create_realm))
+        .route(
+            "/admin/realms/{realm}",
+            get(admin::get_realm).put(admin::update_realm),
+        )
+        .route(
+            "/admin/realms/{realm}/clients",
+            get(admin::query_clients).post(admin::create_client),
+        )
+        .route(
+            "/admin/realms/{realm}/clients/{client}",
+            put(admin::update_client),
+        )
+        .route(
+            "/admin/realms/{realm}/clients/{client}/roles",
+            get(admin::query_client_roles).post(admin::create_client_role),
+        )
+        .route(
+            "/admin/realms/{realm}/clients/{client}/roles/{role}",
+            get(admin::get_client_role).put(admin::update_client_role),
+        )
+        .route(
+            "/admin/realms/{realm}/clients/{client}/roles/{role}/composites",
+            post(admin::add_client_role_composites),
+        )
+        .route(
+            "/admin/realms/{realm}/roles/{role}/composites",
+            post(admin::add_realm_role_composites),
+        )
+        .route(
+            "/admin/realms/{realm}/users",
+            get(admin::query_users).post(admin::create_user),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}",
+            get(admin::get_user)
+                .put(admin::update_user)
+                .delete(admin::delete_user),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/reset-password",
+            put(admin::reset_password),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/execute-actions-email",
+            put(admin::execute_actions_email),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/sessions",
+            get(admin::query_user_sessions),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/logout",
+            post(admin::logout_user),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/role-mappings/clients/{client}",
+            post(admin::add_user_client_roles).delete(admin::remove_user_client_roles),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/role-mappings/clients/{client}/composite",
+            get(admin::query_user_client_roles),
+        )
+        .route(
+            "/admin/realms/{realm}/users/{user}/groups/{group}",
+            put(admin::add_user_to_group),
+        )
+        .route(
+            "/admin/realms/{realm}/groups",
+            get(admin::query_groups).post(admin::create_group),
+        )
+        .route(
+            "/admin/realms/{realm}/groups/{group}/role-mappings/clients/{client}",
+            post(admin::add_group_client_roles),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_admin_token,
+        ));
+
+    Router::new()
+        .route(
+            "/realms/{realm}/protocol/openid-connect/token",
+            post(openid::token),
+        )
+        .route(
+            "/realms/{realm}/protocol/openid-connect/certs",
+            get(openid::certs),
+        )
+        .route(
+            "/realms/{realm}/protocol/openid-connect/logout",
+            post(openid::logout),
+        )
+        .merge(admin)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            record_and_inject_failures,
+        ))
+        .with_state(state)
+}
+
+async fn record_and_inject_failures(
+    State(state): State<MockState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().to_string();
+
+    let failure = {
+        let mut data = state.lock();
+        data.requests.push((method.clone(), path.clone()));
+        data.take_failure(&method, &path)
+    };
+
+    match failure {
+        Some(status) => MockError::new(status, "injected failure").into_response(),
+        None => next.run(request).await,
+    }
+}
+
+async fn require_admin_token(
+    State(state): State<MockState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| state.lock().admin_tokens.contains(token));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        MockError::new(StatusCode::UNAUTHORIZED, "HTTP 401 Unauthorized").into_response()
+    }
+}
@@ -0,0 +1,31 @@
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use serde_json::{Value, json};
+
+const SIGNING_KEY: &str = include_str!("signing_key.pem");
+const KEY_ID: &str = "mock-signing-key";
+const MODULUS: &str = "rnVog4CaUAqKYJIAOjECCsvyKFFVuchPpm2waAkZTmmiFa3DrkJkv-3X7vRQNr8XSylmFzG8QdbZJudRdjq1GsrNWrLduPcA4jqOqpXjwFIwHVx2vFLOaRiR_QDG8hQ1iGBbrnI1sX5CY3BCBnqSe2FdFidzai0xmA1pRVTrxUoo-CjdrQYQDrxKOszb9KCY0VMBQVRk9wqCsQc2xEZr_39FM4QS4XYznn-0YQiBbPMGOuBLE3EUb9rXL2JRfLHz8pzSaqio46_f5L7Mwc1MQNdecWhEueg0x6gyU8-oUMCXVQLsmqrHsA3eTpSpbhECnvHrSalEmB4om_3fhD7mWw";
+const EXPONENT: &str = "AQAB";
+
+pub fn jwks() -> Value {
+    json!({
+        "keys": [{
+            "kid": KEY_ID,
+            "kty": "RSA",
+            "alg": "RS256",
+            "use": "sig",
+            "n": MODULUS,
+            "e": EXPONENT,
+        }]
+    })
+}
+
+pub fn sign(claims: &Value) -> String {
+    let header = Header {
+        kid: Some(KEY_ID.to_owned()),
+        ..Header::new(Algorithm::RS256)
+    };
+
+    let key =
+        EncodingKey::from_rsa_pem(SIGNING_KEY.as_bytes()).expect("mock signing key is invalid");
+    encode(&header, claims, &key).expect("mock token cannot be signed")
+}
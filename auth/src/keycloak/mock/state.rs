@@ -0,0 +1,260 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use http::{Method, StatusCode};
+use serde_json::json;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+pub fn new_id(prefix: &str) -> String {
+    format!("{prefix}-{0}", NEXT_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+pub struct MockError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl MockError {
+    pub fn new(status: StatusCode, message: &str) -> Self {
+        MockError {
+            status,
+            message: message.to_owned(),
+        }
+    }
+
+    pub fn not_found(what: &str) -> Self {
+        MockError::new(StatusCode::NOT_FOUND, &format!("{what} not found"))
+    }
+}
+
+impl IntoResponse for MockError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "errorMessage": self.message }))).into_response()
+    }
+}
+
+pub struct InjectedFailure {
+    pub method: Method,
+    pub path: String,
+    pub status: StatusCode,
+    pub remaining: usize,
+}
+
+pub struct MockRole {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub composites: Vec<String>,
+}
+
+pub struct MockClient {
+    pub id: String,
+    pub client_id: String,
+    pub enabled: bool,
+    pub public_client: bool,
+    pub secret: String,
+    pub direct_access_grants_enabled: bool,
+    pub service_accounts_enabled: bool,
+    pub roles: Vec<MockRole>,
+}
+
+pub struct MockGroup {
+    pub id: String,
+    pub name: String,
+    pub roles: Vec<String>,
+}
+
+pub struct MockUser {
+    pub id: String,
+    pub username: String,
+    pub enabled: bool,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub attributes: HashMap<String, Vec<String>>,
+    pub required_actions: Vec<String>,
+    pub password: Option<String>,
+    pub roles: Vec<String>,
+    pub groups: Vec<String>,
+    pub sent_actions: Vec<String>,
+}
+
+pub struct MockRealm {
+    pub name: String,
+    pub enabled: bool,
+    pub verify_email: bool,
+    pub smtp_server: HashMap<String, String>,
+    pub clients: Vec<MockClient>,
+    pub users: Vec<MockUser>,
+    pub groups: Vec<MockGroup>,
+    pub default_roles: Vec<String>,
+    pub sessions: HashMap<String, String>,
+}
+
+impl MockRealm {
+    pub fn new(name: &str, enabled: bool) -> Self {
+        MockRealm {
+            name: name.to_owned(),
+            enabled,
+            verify_email: false,
+            smtp_server: HashMap::new(),
+            clients: vec![],
+            users: vec![],
+            groups: vec![],
+            default_roles: vec![],
+            sessions: HashMap::new(),
+        }
+    }
+
+    pub fn client(&self, client_id: &str) -> Option<&MockClient> {
+        self.clients
+            .iter()
+            .find(|client| client.client_id == client_id)
+    }
+
+    pub fn client_by_uuid_mut(&mut self, id: &str) -> Result<&mut MockClient, MockError> {
+        self.clients
+            .iter_mut()
+            .find(|client| client.id == id)
+            .ok_or_else(|| MockError::not_found("client"))
+    }
+
+    pub fn user(&self, username: &str) -> Option<&MockUser> {
+        self.users
+            .iter()
+            .find(|user| user.username.eq_ignore_ascii_case(username))
+    }
+
+    pub fn user_by_uuid_mut(&mut self, id: &str) -> Result<&mut MockUser, MockError> {
+        self.users
+            .iter_mut()
+            .find(|user| user.id == id)
+            .ok_or_else(|| MockError::not_found("user"))
+    }
+
+    pub fn group(&self, name: &str) -> Option<&MockGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    pub fn role(&self, id: &str) -> Option<&MockRole> {
+        self.clients
+            .iter()
+            .flat_map(|client| client.roles.iter())
+            .find(|role| role.id == id)
+    }
+
+    pub fn user_client_roles(&self, user: &MockUser, client_uuid: &str) -> Vec<&MockRole> {
+        let client_roles: HashSet<&str> = self
+            .clients
+            .iter()
+            .filter(|client| client.id == client_uuid)
+            .flat_map(|client| client.roles.iter().map(|role| role.id.as_str()))
+            .collect();
+
+        let mut pending: Vec<&str> = user
+            .roles
+            .iter()
+            .chain(self.default_roles.iter())
+            .chain(
+                self.groups
+                    .iter()
+                    .filter(|group| user.groups.contains(&group.id))
+                    .flat_map(|group| group.roles.iter()),
+            )
+            .map(String::as_str)
+            .collect();
+
+        let mut effective = HashSet::new();
+        while let Some(id) = pending.pop() {
+            if effective.insert(id)
+                && let Some(role) = self.role(id)
+            {
+                pending.extend(role.composites.iter().map(String::as_str));
+            }
+        }
+
+        effective
+            .into_iter()
+            .filter(|id| client_roles.contains(id))
+            .filter_map(|id| self.role(id))
+            .collect()
+    }
+}
+
+pub struct MockData {
+    pub host: String,
+    pub realms: Vec<MockRealm>,
+    pub admin_login: String,
+    pub admin_password: String,
+    pub admin_tokens: HashSet<String>,
+    pub admin_refresh_tokens: HashSet<String>,
+    pub admin_grants: Vec<String>,
+    pub token_lifetime: u64,
+    pub failures: Vec<InjectedFailure>,
+    pub requests: Vec<(Method, String)>,
+}
+
+impl Default for MockData {
+    fn default() -> Self {
+        MockData {
+            host: String::new(),
+            realms: vec![MockRealm::new("master", true)],
+            admin_login: "admin".to_owned(),
+            admin_password: "admin".to_owned(),
+            admin_tokens: HashSet::new(),
+            admin_refresh_tokens: HashSet::new(),
+            admin_grants: vec![],
+            token_lifetime: 300,
+            failures: vec![],
+            requests: vec![],
+        }
+    }
+}
+
+impl MockData {
+    pub fn realm(&self, name: &str) -> Option<&MockRealm> {
+        self.realms.iter().find(|realm| realm.name == name)
+    }
+
+    pub fn realm_mut(&mut self, name: &str) -> Result<&mut MockRealm, MockError> {
+        self.realms
+            .iter_mut()
+            .find(|realm| realm.name == name)
+            .ok_or_else(|| MockError::not_found("realm"))
+    }
+
+    pub fn take_failure(&mut self, method: &Method, path: &str) -> Option<StatusCode> {
+        let failure = self
+            .failures
+            .iter_mut()
+            .find(|failure| failure.method == method && path.contains(&failure.path))?;
+
+        failure.remaining -= 1;
+        let status = failure.status;
+
+        self.failures.retain(|failure| failure.remaining > 0);
+        Some(status)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MockState(Arc<Mutex<MockData>>);
+
+impl MockState {
+    pub fn lock(&self) -> MutexGuard<'_, MockData> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -1,3 +1,5 @@
 pub mod services;
 pub mod keycloak_ex;
-pub mod keycloak_factory;
\ No newline at end of file
+pub mod keycloak_factory;
+#[cfg(test)]
+pub mod mock;
\ No newline at end of file
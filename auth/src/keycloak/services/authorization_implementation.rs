@@ -1,7 +1,11 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use reqwest::{Client, Response};
-use tokio::select;
+use tokio::{select, sync::Mutex};
 use tokio_util::sync::CancellationToken;
 use utils::{errors::AppErr, http::ResponseExtended};
 
@@ -10,6 +14,38 @@ use super::{
     responses::access_token::AccessTokenResponse, routes::AdminRoutes,
 };
 
+const EXPIRATION_MARGIN: Duration = Duration::from_secs(30);
+
+struct CachedAccessToken {
+    token: AccessTokenResponse,
+    expires_at: Instant,
+    refresh_expires_at: Instant,
+}
+
+impl CachedAccessToken {
+    fn new(token: AccessTokenResponse) -> Self {
+        let received_at = Instant::now();
+
+        CachedAccessToken {
+            expires_at: received_at + Duration::from_secs(token.expires_in),
+            refresh_expires_at: received_at + Duration::from_secs(token.refresh_expires_in),
+            token,
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        Instant::now() + EXPIRATION_MARGIN < self.expires_at
+    }
+
+    fn refresh_token(&self) -> Option<&str> {
+        if Instant::now() + EXPIRATION_MARGIN < self.refresh_expires_at {
+            self.token.refresh_token.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
 pub struct DefaultAdminTokenProvider<TRoutes, TAdminCredentialProvider>
 where
     TRoutes: AdminRoutes,
@@ -17,6 +53,8 @@ where
 {
     routes: Arc<TRoutes>,
     credentials_provider: Arc<TAdminCredentialProvider>,
+    client: Arc<Client>,
+    cache: Mutex<Option<CachedAccessToken>>,
 }
 
 impl<TRoutes, TAdminCredentialProvider> DefaultAdminTokenProvider<TRoutes, TAdminCredentialProvider>
@@ -24,25 +62,51 @@ where
     TRoutes: AdminRoutes,
     TAdminCredentialProvider: AdminCredentialProvider,
 {
-    pub fn new(routes: Arc<TRoutes>, credentials_provider: Arc<TAdminCredentialProvider>) -> Self {
+    pub fn new(
+        routes: Arc<TRoutes>,
+        credentials_provider: Arc<TAdminCredentialProvider>,
+        client: Arc<Client>,
+    ) -> Self {
         DefaultAdminTokenProvider {
             routes,
             credentials_provider,
+            client,
+            cache: Mutex::new(None),
         }
     }
 }
 
-impl<TRoutes, TAdminCredentialProvider> AdminAccessTokenProvider
-    for DefaultAdminTokenProvider<TRoutes, TAdminCredentialProvider>
+impl<TRoutes, TAdminCredentialProvider> DefaultAdminTokenProvider<TRoutes, TAdminCredentialProvider>
 where
     TRoutes: AdminRoutes + Send + Sync,
     TAdminCredentialProvider: AdminCredentialProvider + Send + Sync,
 {
-    async fn get_access_token_with_cancel(
+    async fn request_token(
         &self,
+        form_data: &HashMap<&str, &str>,
         cancellation_token: &CancellationToken,
     ) -> Result<AccessTokenResponse, AppErr> {
         let auth_route = self.routes.get_access_token_route().await?;
+
+        let auth_response = select! {
+            resp = self.client
+            .post(&auth_route)
+            .form(form_data)
+            .send() => resp.map_err(|err| AppErr::from_owned(format!("admin auth call err: {err}"))),
+            _ = cancellation_token.cancelled() => Result::<Response, AppErr>::Err(AppErr::from("auth call cancelled"))
+
+        }?;
+
+        let token = auth_response
+            .ensure_success_json::<AccessTokenResponse>()
+            .await?;
+        Ok(token)
+    }
+
+    async fn login(
+        &self,
+        cancellation_token: &CancellationToken,
+    ) -> Result<AccessTokenResponse, AppErr> {
         let login = self.credentials_provider.get_login().await?;
         let password = self.credentials_provider.get_password().await?;
 
@@ -52,18 +116,57 @@ where
         form_data.insert("password", &password);
         form_data.insert("grant_type", "password");
 
-        let auth_response = select! {
-            resp = Client::new()
-            .post(&auth_route)
-            .form(&form_data)
-            .send() => resp.map_err(|err| AppErr::from_owned(format!("admin auth call err: {err}"))),
-            _ = cancellation_token.cancelled() => Result::<Response, AppErr>::Err(AppErr::from("auth call cancelled"))
+        self.request_token(&form_data, cancellation_token).await
+    }
 
+    async fn refresh(
+        &self,
+        refresh_token: &str,
+        cancellation_token: &CancellationToken,
+    ) -> Result<AccessTokenResponse, AppErr> {
+        let mut form_data = HashMap::new();
+        form_data.insert("client_id", "admin-cli");
+        form_data.insert("refresh_token", refresh_token);
+        form_data.insert("grant_type", "refresh_token");
+
+        self.request_token(&form_data, cancellation_token).await
+    }
+}
+
+impl<TRoutes, TAdminCredentialProvider> AdminAccessTokenProvider
+    for DefaultAdminTokenProvider<TRoutes, TAdminCredentialProvider>
+where
+    TRoutes: AdminRoutes + Send + Sync,
+    TAdminCredentialProvider: AdminCredentialProvider + Send + Sync,
+{
+    async fn get_access_token_with_cancel(
+        &self,
+        cancellation_token: &CancellationToken,
+    ) -> Result<AccessTokenResponse, AppErr> {
+        let mut cache = select! {
+            guard = self.cache.lock() => Ok(guard),
+            _ = cancellation_token.cancelled() => Err(AppErr::from("auth call cancelled"))
         }?;
 
-        let token = auth_response
-            .ensure_success_json::<AccessTokenResponse>()
-            .await?;
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.is_fresh()) {
+            return Ok(cached.token.clone());
+        }
+
+        let refreshed = match cache.as_ref().and_then(CachedAccessToken::refresh_token) {
+            Some(refresh_token) => self
+                .refresh(refresh_token, cancellation_token)
+                .await
+                .inspect_err(|err| log::warn!("admin token refresh failed: {err}"))
+                .ok(),
+            None => None,
+        };
+
+        let token = match refreshed {
+            Some(token) => token,
+            None => self.login(cancellation_token).await?,
+        };
+
+        *cache = Some(CachedAccessToken::new(token.clone()));
         Ok(token)
     }
 
@@ -72,3 +175,49 @@ where
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use crate::keycloak::{
+        mock::server::MockKeycloak,
+        services::{management::KeycloakManagement, queries::realm::RealmQuery},
+    };
+
+    #[tokio::test]
+    async fn reuses_admin_token_between_calls() {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        for _ in 0..3 {
+            manager
+                .query_realm(&RealmQuery::new(&"master"))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(keycloak.request_count(Method::POST, "/token"), 1);
+    }
+
+    #[tokio::test]
+    async fn refreshes_expired_admin_token() {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+        keycloak.with_data(|data| data.token_lifetime = 10);
+
+        manager
+            .query_realm(&RealmQuery::new(&"master"))
+            .await
+            .unwrap();
+        manager
+            .query_realm(&RealmQuery::new(&"master"))
+            .await
+            .unwrap();
+
+        assert_eq!(keycloak.request_count(Method::POST, "/token"), 2);
+        keycloak.with_data(|data| {
+            assert_eq!(data.admin_grants, ["password", "refresh_token"]);
+        });
+    }
+}
@@ -1,31 +1,29 @@
-use std::{env, fmt::Display};
+use std::fmt::Display;
 
 use utils::errors::AppErr;
 
 use super::credentials::AdminCredentialProvider;
 
-pub struct EnvAdminCredentialProvider {
-    login_env: String,
-    password_env: String,
+pub struct ConfiguredAdminCredentialProvider {
+    login: String,
+    password: String,
 }
 
-impl EnvAdminCredentialProvider {
-    pub fn new(login_env: &impl Display, password_env: &impl Display) -> Self {
-        EnvAdminCredentialProvider {
-            login_env: login_env.to_string(),
-            password_env: password_env.to_string(),
+impl ConfiguredAdminCredentialProvider {
+    pub fn new(login: &impl Display, password: &impl Display) -> Self {
+        ConfiguredAdminCredentialProvider {
+            login: login.to_string(),
+            password: password.to_string(),
         }
     }
 }
 
-impl AdminCredentialProvider for EnvAdminCredentialProvider {
+impl AdminCredentialProvider for ConfiguredAdminCredentialProvider {
     async fn get_login(&self) -> Result<String, AppErr> {
-        env::var(&self.login_env)
-            .map_err(|err| AppErr::from_owned(format!("cannot get login env: {err}")))
+        Ok(self.login.clone())
     }
 
     async fn get_password(&self) -> Result<String, AppErr> {
-        env::var(&self.password_env)
-            .map_err(|err| AppErr::from_owned(format!("cannot get password env: {err}")))
+        Ok(self.password.clone())
     }
 }
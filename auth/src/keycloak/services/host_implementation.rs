@@ -1,24 +1,23 @@
-use std::{env, fmt::Display};
+use std::fmt::Display;
 
 use utils::errors::AppErr;
 
 use super::host::HostAddressProvider;
 
-pub struct EnvHostAddressProvider {
-    host_env: String,
+pub struct ConfiguredHostAddressProvider {
+    host: String,
 }
 
-impl EnvHostAddressProvider {
-    pub fn new(host_env: &impl Display) -> Self {
-        EnvHostAddressProvider {
-            host_env: host_env.to_string(),
+impl ConfiguredHostAddressProvider {
+    pub fn new(host: &impl Display) -> Self {
+        ConfiguredHostAddressProvider {
+            host: host.to_string(),
         }
     }
 }
 
-impl HostAddressProvider for EnvHostAddressProvider {
+impl HostAddressProvider for ConfiguredHostAddressProvider {
     async fn get_host(&self) -> Result<String, AppErr> {
-        env::var(&self.host_env)
-            .map_err(|err| AppErr::from_owned(format!("cannot get login env: {err}")))
+        Ok(self.host.clone())
     }
 }
@@ -2,13 +2,27 @@ use tokio_util::sync::CancellationToken;
 use utils::errors::AppErr;
 
 use super::{
-    queries::{clients::ClientsQuery, role::RoleQuery, users::UsersQuery},
+    queries::{
+        client_roles::ClientRolesQuery, clients::ClientsQuery, groups::GroupsQuery,
+        realm::RealmQuery, role::RoleQuery, user::UserQuery,
+        user_client_roles::UserClientRolesQuery, user_sessions::UserSessionsQuery,
+        users::UsersQuery,
+    },
     requests::{
+        add_default_roles::AddDefaultRolesRequest, add_role_composites::AddRoleCompositesRequest,
+        add_user_to_group::AddUserToGroupRequest, assign_group_roles::AssignGroupRolesRequest,
         assign_roles::AssignRolesRequest, create_client::CreateClientRequest,
-        create_realm::CreateRealmRequest, create_role::CreateRoleRequest,
-        create_user::CreateUserRequest, update_users_email_request::UpdateUsersEmailRequest,
+        create_group::CreateGroupRequest, create_realm::CreateRealmRequest,
+        create_role::CreateRoleRequest, create_user::CreateUserRequest,
+        delete_user::DeleteUserRequest, execute_actions_email::ExecuteActionsEmailRequest,
+        logout_user::LogoutUserRequest, reset_password::ResetPasswordRequest,
+        update_client::UpdateClientRequest, update_realm::UpdateRealmRequest,
+        update_role::UpdateRoleRequest, update_users_email_request::UpdateUsersEmailRequest,
+    },
+    responses::{
+        client::ClientResponse, group::GroupResponse, realm::RealmResponse, role::RoleResponse,
+        user::UserResponse, user_session::UserSessionResponse,
     },
-    responses::{client::ClientResponse, role::RoleResponse, user::UserResponse},
 };
 
 pub trait KeycloakManagement {
@@ -100,6 +114,17 @@ pub trait KeycloakManagement {
         cancellation_token: &CancellationToken,
     ) -> impl Future<Output = Result<(), AppErr>> + Send;
 
+    fn remove_roles(
+        &self,
+        request: &AssignRolesRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn remove_roles_with_cancel(
+        &self,
+        request: &AssignRolesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
     fn update_users_email(
         &self,
         request: &UpdateUsersEmailRequest,
@@ -110,4 +135,202 @@ pub trait KeycloakManagement {
         request: &UpdateUsersEmailRequest,
         cancellation_token: &CancellationToken,
     ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn delete_user(
+        &self,
+        request: &DeleteUserRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn delete_user_with_cancel(
+        &self,
+        request: &DeleteUserRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn query_realm(
+        &self,
+        request: &RealmQuery,
+    ) -> impl Future<Output = Result<Option<RealmResponse>, AppErr>> + Send;
+
+    fn query_realm_with_cancel(
+        &self,
+        request: &RealmQuery,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<Option<RealmResponse>, AppErr>> + Send;
+
+    fn update_realm(
+        &self,
+        request: &UpdateRealmRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn update_realm_with_cancel(
+        &self,
+        request: &UpdateRealmRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn update_client(
+        &self,
+        request: &UpdateClientRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn update_client_with_cancel(
+        &self,
+        request: &UpdateClientRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn query_client_roles(
+        &self,
+        request: &ClientRolesQuery,
+    ) -> impl Future<Output = Result<Vec<RoleResponse>, AppErr>> + Send;
+
+    fn query_client_roles_with_cancel(
+        &self,
+        request: &ClientRolesQuery,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<RoleResponse>, AppErr>> + Send;
+
+    fn update_role(
+        &self,
+        request: &UpdateRoleRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn update_role_with_cancel(
+        &self,
+        request: &UpdateRoleRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn add_role_composites(
+        &self,
+        request: &AddRoleCompositesRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn add_role_composites_with_cancel(
+        &self,
+        request: &AddRoleCompositesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn add_default_roles(
+        &self,
+        request: &AddDefaultRolesRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn add_default_roles_with_cancel(
+        &self,
+        request: &AddDefaultRolesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn query_groups(
+        &self,
+        request: &GroupsQuery,
+    ) -> impl Future<Output = Result<Vec<GroupResponse>, AppErr>> + Send;
+
+    fn query_groups_with_cancel(
+        &self,
+        request: &GroupsQuery,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<GroupResponse>, AppErr>> + Send;
+
+    fn create_group(
+        &self,
+        request: &CreateGroupRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn create_group_with_cancel(
+        &self,
+        request: &CreateGroupRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn assign_group_roles(
+        &self,
+        request: &AssignGroupRolesRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn assign_group_roles_with_cancel(
+        &self,
+        request: &AssignGroupRolesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn add_user_to_group(
+        &self,
+        request: &AddUserToGroupRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn add_user_to_group_with_cancel(
+        &self,
+        request: &AddUserToGroupRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn execute_actions_email(
+        &self,
+        request: &ExecuteActionsEmailRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn execute_actions_email_with_cancel(
+        &self,
+        request: &ExecuteActionsEmailRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn reset_password(
+        &self,
+        request: &ResetPasswordRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn reset_password_with_cancel(
+        &self,
+        request: &ResetPasswordRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn get_user_by_id(
+        &self,
+        request: &UserQuery,
+    ) -> impl Future<Output = Result<Option<UserResponse>, AppErr>> + Send;
+
+    fn get_user_by_id_with_cancel(
+        &self,
+        request: &UserQuery,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<Option<UserResponse>, AppErr>> + Send;
+
+    fn query_user_client_roles(
+        &self,
+        request: &UserClientRolesQuery,
+    ) -> impl Future<Output = Result<Vec<RoleResponse>, AppErr>> + Send;
+
+    fn query_user_client_roles_with_cancel(
+        &self,
+        request: &UserClientRolesQuery,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<RoleResponse>, AppErr>> + Send;
+
+    fn query_user_sessions(
+        &self,
+        request: &UserSessionsQuery,
+    ) -> impl Future<Output = Result<Vec<UserSessionResponse>, AppErr>> + Send;
+
+    fn query_user_sessions_with_cancel(
+        &self,
+        request: &UserSessionsQuery,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<UserSessionResponse>, AppErr>> + Send;
+
+    fn logout_user(
+        &self,
+        request: &LogoutUserRequest,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn logout_user_with_cancel(
+        &self,
+        request: &LogoutUserRequest,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
 }
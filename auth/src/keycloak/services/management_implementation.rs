@@ -1,23 +1,37 @@
 use std::sync::Arc;
 
-use reqwest::{Client, Response};
+use reqwest::{Client, Response, StatusCode};
 use tokio::select;
 use tokio_util::sync::CancellationToken;
 use utils::{
-    errors::AppErr,
+    errors::{AppErr, AppErrKind},
     http::{ResponseExtended, SendExtended},
 };
 
 use super::{
     authorization::AdminAccessTokenProvider,
     management::KeycloakManagement,
-    queries::{clients::ClientsQuery, role::RoleQuery, users::UsersQuery},
+    queries::{
+        client_roles::ClientRolesQuery, clients::ClientsQuery, groups::GroupsQuery,
+        realm::RealmQuery, role::RoleQuery, user::UserQuery,
+        user_client_roles::UserClientRolesQuery, user_sessions::UserSessionsQuery,
+        users::UsersQuery,
+    },
     requests::{
+        add_default_roles::AddDefaultRolesRequest, add_role_composites::AddRoleCompositesRequest,
+        add_user_to_group::AddUserToGroupRequest, assign_group_roles::AssignGroupRolesRequest,
         assign_roles::AssignRolesRequest, create_client::CreateClientRequest,
-        create_realm::CreateRealmRequest, create_role::CreateRoleRequest,
-        create_user::CreateUserRequest, update_users_email_request::UpdateUsersEmailRequest,
+        create_group::CreateGroupRequest, create_realm::CreateRealmRequest,
+        create_role::CreateRoleRequest, create_user::CreateUserRequest,
+        delete_user::DeleteUserRequest, execute_actions_email::ExecuteActionsEmailRequest,
+        logout_user::LogoutUserRequest, reset_password::ResetPasswordRequest,
+        update_client::UpdateClientRequest, update_realm::UpdateRealmRequest,
+        update_role::UpdateRoleRequest, update_users_email_request::UpdateUsersEmailRequest,
+    },
+    responses::{
+        client::ClientResponse, group::GroupResponse, realm::RealmResponse, role::RoleResponse,
+        user::UserResponse, user_session::UserSessionResponse,
     },
-    responses::{client::ClientResponse, role::RoleResponse, user::UserResponse},
     routes::AdminRoutes,
 };
 
@@ -28,6 +42,7 @@ where
 {
     auth_provider: Arc<TAuthorization>,
     routes: Arc<TRoutes>,
+    client: Arc<Client>,
 }
 
 impl<TAuthorization, TRoutes> DefaultKeycloakManagement<TAuthorization, TRoutes>
@@ -35,10 +50,15 @@ where
     TAuthorization: AdminAccessTokenProvider,
     TRoutes: AdminRoutes,
 {
-    pub fn new(auth_provider: Arc<TAuthorization>, routes: Arc<TRoutes>) -> Self {
+    pub fn new(
+        auth_provider: Arc<TAuthorization>,
+        routes: Arc<TRoutes>,
+        client: Arc<Client>,
+    ) -> Self {
         DefaultKeycloakManagement {
             auth_provider,
             routes,
+            client,
         }
     }
 }
@@ -62,7 +82,7 @@ where
             .await?;
 
         let create_realm_response = select! {
-            resp = Client::new().quick_post(&url, request, Some(token.access_token)) => resp,
+            resp = self.client.quick_post(&url, request, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -83,7 +103,7 @@ where
             .await?;
 
         let create_client_response = select! {
-            resp = Client::new().quick_post(&url, request, Some(token.access_token)) => resp,
+            resp = self.client.quick_post(&url, request, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -104,11 +124,20 @@ where
             .await?;
 
         let create_user_response = select! {
-            resp = Client::new().quick_post(&url, request, Some(token.access_token)) => resp,
+            resp = self.client.quick_post(&url, request, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => Result::<Response, AppErr>::Err(AppErr::from("create realm request cancelled"))
         }?;
 
-        create_user_response.ensure_success().await?;
+        create_user_response
+            .ensure_success()
+            .await
+            .map_err(|err| match err.kind() {
+                AppErrKind::Conflict => {
+                    AppErr::from_owned(format!("user {0} already exists", request.username))
+                        .with_kind(AppErrKind::Conflict)
+                }
+                _ => err,
+            })?;
         Ok(())
     }
 
@@ -128,7 +157,7 @@ where
             .await?;
 
         let response = select! {
-            resp = Client::new().quick_get(&url, Some(token.access_token)) => resp,
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -152,7 +181,7 @@ where
             .await?;
 
         let response = select! {
-            resp = Client::new().quick_get(&url, Some(token.access_token)) => resp,
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -178,7 +207,7 @@ where
             .await?;
 
         let create_role_response = select! {
-            resp = Client::new().quick_post(&url, request, Some(token.access_token)) => resp,
+            resp = self.client.quick_post(&url, request, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -202,7 +231,7 @@ where
             .await?;
 
         let response = select! {
-            resp = Client::new().quick_get(&url, Some(token.access_token)) => resp,
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -226,7 +255,31 @@ where
             .await?;
 
         let response = select! {
-            resp = Client::new().quick_post(&url, &request.assign_roles, Some(token.access_token)) =>  resp,
+            resp = self.client.quick_post(&url, &request.assign_roles, Some(token.access_token)) =>  resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn remove_roles_with_cancel(
+        &self,
+        request: &AssignRolesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_assign_roles_query_route(&request.realm, &request.user_uuid, &request.client_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_delete_with_body(&url, &request.assign_roles, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -250,7 +303,441 @@ where
             .await?;
 
         let response = select! {
-            resp = Client::new().quick_put(&url, &request, Some(token.access_token)) => resp,
+            resp = self.client.quick_put(&url, &request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn delete_user_with_cancel(
+        &self,
+        request: &DeleteUserRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_delete_user_route(&request.realm, &request.user_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_delete(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn query_realm_with_cancel(
+        &self,
+        request: &RealmQuery,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Option<RealmResponse>, AppErr> {
+        let url = self.routes.get_realm_query_route(&request.realm).await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let realm = response.ensure_success_json::<RealmResponse>().await?;
+        Ok(Some(realm))
+    }
+
+    async fn update_realm_with_cancel(
+        &self,
+        request: &UpdateRealmRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self.routes.get_update_realm_route(&request.realm).await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_put(&url, &request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn update_client_with_cancel(
+        &self,
+        request: &UpdateClientRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_update_client_route(&request.realm, &request.client_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_put(&url, &request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn query_client_roles_with_cancel(
+        &self,
+        request: &ClientRolesQuery,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Vec<RoleResponse>, AppErr> {
+        let url = self
+            .routes
+            .get_client_roles_query_route(&request.realm, &request.client_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        let roles = response.ensure_success_json::<Vec<RoleResponse>>().await?;
+        Ok(roles)
+    }
+
+    async fn update_role_with_cancel(
+        &self,
+        request: &UpdateRoleRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_update_role_route(&request.realm, &request.client_uuid, &request.name)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_put(&url, &request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn add_role_composites_with_cancel(
+        &self,
+        request: &AddRoleCompositesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_role_composites_route(&request.realm, &request.client_uuid, &request.role_name)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_post(&url, &request.composites, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn add_default_roles_with_cancel(
+        &self,
+        request: &AddDefaultRolesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self.routes.get_default_roles_route(&request.realm).await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_post(&url, &request.roles, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn query_groups_with_cancel(
+        &self,
+        request: &GroupsQuery,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Vec<GroupResponse>, AppErr> {
+        let url = self
+            .routes
+            .get_groups_query_route(&request.realm, &request.name)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        let groups = response.ensure_success_json::<Vec<GroupResponse>>().await?;
+        Ok(groups)
+    }
+
+    async fn create_group_with_cancel(
+        &self,
+        request: &CreateGroupRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self.routes.get_create_group_route(&request.realm).await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_post(&url, request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn assign_group_roles_with_cancel(
+        &self,
+        request: &AssignGroupRolesRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_assign_group_roles_route(&request.realm, &request.group_uuid, &request.client_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_post(&url, &request.assign_roles, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn add_user_to_group_with_cancel(
+        &self,
+        request: &AddUserToGroupRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_add_user_to_group_route(&request.realm, &request.user_uuid, &request.group_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_put(&url, request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn execute_actions_email_with_cancel(
+        &self,
+        request: &ExecuteActionsEmailRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_execute_actions_email_route(&request.realm, &request.user_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_put(&url, &request.actions, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn reset_password_with_cancel(
+        &self,
+        request: &ResetPasswordRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_reset_password_route(&request.realm, &request.user_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_put(&url, request, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        response.ensure_success().await?;
+        Ok(())
+    }
+
+    async fn get_user_by_id_with_cancel(
+        &self,
+        request: &UserQuery,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Option<UserResponse>, AppErr> {
+        let url = self
+            .routes
+            .get_user_query_route(&request.realm, &request.user_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let user = response.ensure_success_json::<UserResponse>().await?;
+        Ok(Some(user))
+    }
+
+    async fn query_user_client_roles_with_cancel(
+        &self,
+        request: &UserClientRolesQuery,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Vec<RoleResponse>, AppErr> {
+        let url = self
+            .routes
+            .get_user_client_roles_query_route(
+                &request.realm,
+                &request.user_uuid,
+                &request.client_uuid,
+            )
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        let roles = response.ensure_success_json::<Vec<RoleResponse>>().await?;
+        Ok(roles)
+    }
+
+    async fn query_user_sessions_with_cancel(
+        &self,
+        request: &UserSessionsQuery,
+        cancellation_token: &CancellationToken,
+    ) -> Result<Vec<UserSessionResponse>, AppErr> {
+        let url = self
+            .routes
+            .get_user_sessions_query_route(&request.realm, &request.user_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_get(&url, Some(token.access_token)) => resp,
+            _ = cancellation_token.cancelled() => AppErr::cancelled()
+        }?;
+
+        let sessions = response
+            .ensure_success_json::<Vec<UserSessionResponse>>()
+            .await?;
+        Ok(sessions)
+    }
+
+    async fn logout_user_with_cancel(
+        &self,
+        request: &LogoutUserRequest,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), AppErr> {
+        let url = self
+            .routes
+            .get_logout_user_route(&request.realm, &request.user_uuid)
+            .await?;
+
+        let token = self
+            .auth_provider
+            .get_access_token_with_cancel(cancellation_token)
+            .await?;
+
+        let response = select! {
+            resp = self.client.quick_post(&url, request, Some(token.access_token)) => resp,
             _ = cancellation_token.cancelled() => AppErr::cancelled()
         }?;
 
@@ -299,8 +786,115 @@ where
             .await
     }
 
+    async fn remove_roles(&self, request: &AssignRolesRequest) -> Result<(), AppErr> {
+        self.remove_roles_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
     async fn update_users_email(&self, request: &UpdateUsersEmailRequest) -> Result<(), AppErr> {
         self.update_users_email_with_cancel(request, &CancellationToken::new())
             .await
     }
+
+    async fn delete_user(&self, request: &DeleteUserRequest) -> Result<(), AppErr> {
+        self.delete_user_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn query_realm(&self, request: &RealmQuery) -> Result<Option<RealmResponse>, AppErr> {
+        self.query_realm_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn update_realm(&self, request: &UpdateRealmRequest) -> Result<(), AppErr> {
+        self.update_realm_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn update_client(&self, request: &UpdateClientRequest) -> Result<(), AppErr> {
+        self.update_client_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn query_client_roles(
+        &self,
+        request: &ClientRolesQuery,
+    ) -> Result<Vec<RoleResponse>, AppErr> {
+        self.query_client_roles_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn update_role(&self, request: &UpdateRoleRequest) -> Result<(), AppErr> {
+        self.update_role_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn add_role_composites(&self, request: &AddRoleCompositesRequest) -> Result<(), AppErr> {
+        self.add_role_composites_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn add_default_roles(&self, request: &AddDefaultRolesRequest) -> Result<(), AppErr> {
+        self.add_default_roles_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn query_groups(&self, request: &GroupsQuery) -> Result<Vec<GroupResponse>, AppErr> {
+        self.query_groups_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn create_group(&self, request: &CreateGroupRequest) -> Result<(), AppErr> {
+        self.create_group_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn assign_group_roles(&self, request: &AssignGroupRolesRequest) -> Result<(), AppErr> {
+        self.assign_group_roles_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn add_user_to_group(&self, request: &AddUserToGroupRequest) -> Result<(), AppErr> {
+        self.add_user_to_group_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn execute_actions_email(
+        &self,
+        request: &ExecuteActionsEmailRequest,
+    ) -> Result<(), AppErr> {
+        self.execute_actions_email_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn reset_password(&self, request: &ResetPasswordRequest) -> Result<(), AppErr> {
+        self.reset_password_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn get_user_by_id(&self, request: &UserQuery) -> Result<Option<UserResponse>, AppErr> {
+        self.get_user_by_id_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn query_user_client_roles(
+        &self,
+        request: &UserClientRolesQuery,
+    ) -> Result<Vec<RoleResponse>, AppErr> {
+        self.query_user_client_roles_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn query_user_sessions(
+        &self,
+        request: &UserSessionsQuery,
+    ) -> Result<Vec<UserSessionResponse>, AppErr> {
+        self.query_user_sessions_with_cancel(request, &CancellationToken::new())
+            .await
+    }
+
+    async fn logout_user(&self, request: &LogoutUserRequest) -> Result<(), AppErr> {
+        self.logout_user_with_cancel(request, &CancellationToken::new())
+            .await
+    }
 }
@@ -14,4 +14,5 @@ pub mod credentials;
 pub mod authorization;
 pub mod management;
 pub mod seeding;
+pub mod realm_description;
 pub mod watcher;
\ No newline at end of file
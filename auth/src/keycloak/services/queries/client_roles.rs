@@ -0,0 +1,15 @@
+use std::fmt::Display;
+
+pub struct ClientRolesQuery {
+    pub realm: String,
+    pub client_uuid: String,
+}
+
+impl ClientRolesQuery {
+    pub fn new(realm: &impl Display, client_uuid: &impl Display) -> Self {
+        ClientRolesQuery {
+            realm: realm.to_string(),
+            client_uuid: client_uuid.to_string(),
+        }
+    }
+}
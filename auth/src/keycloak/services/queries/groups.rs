@@ -0,0 +1,15 @@
+use std::fmt::Display;
+
+pub struct GroupsQuery {
+    pub realm: String,
+    pub name: String,
+}
+
+impl GroupsQuery {
+    pub fn new(realm: &impl Display, name: &impl Display) -> Self {
+        GroupsQuery {
+            realm: realm.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
@@ -1,3 +1,10 @@
-pub mod users;
+pub mod client_roles;
 pub mod clients;
-pub mod role;
\ No newline at end of file
+pub mod realm;
+pub mod role;
+pub mod users;
+
+pub mod groups;
+pub mod user;
+pub mod user_client_roles;
+pub mod user_sessions;
\ No newline at end of file
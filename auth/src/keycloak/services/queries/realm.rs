@@ -0,0 +1,13 @@
+use std::fmt::Display;
+
+pub struct RealmQuery {
+    pub realm: String,
+}
+
+impl RealmQuery {
+    pub fn new(realm: &impl Display) -> Self {
+        RealmQuery {
+            realm: realm.to_string(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use std::fmt::Display;
+
+pub struct UserQuery {
+    pub realm: String,
+    pub user_uuid: String,
+}
+
+impl UserQuery {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display) -> Self {
+        UserQuery {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use std::fmt::Display;
+
+pub struct UserClientRolesQuery {
+    pub realm: String,
+    pub user_uuid: String,
+    pub client_uuid: String,
+}
+
+impl UserClientRolesQuery {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display, client_uuid: &impl Display) -> Self {
+        UserClientRolesQuery {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+            client_uuid: client_uuid.to_string(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use std::fmt::Display;
+
+pub struct UserSessionsQuery {
+    pub realm: String,
+    pub user_uuid: String,
+}
+
+impl UserSessionsQuery {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display) -> Self {
+        UserSessionsQuery {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+        }
+    }
+}
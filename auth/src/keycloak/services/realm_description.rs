@@ -0,0 +1,280 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use utils::{config::ConfigSource, errors::AppErr};
+
+use super::seeding::KeycloakSeedingArguments;
+
+#[derive(Deserialize)]
+pub struct RealmDescription {
+    pub realm: String,
+    #[serde(default)]
+    pub verify_email: bool,
+    pub smtp_server: Option<SmtpServerDescription>,
+    #[serde(default)]
+    pub clients: Vec<ClientDescription>,
+    #[serde(default)]
+    pub default_roles: Vec<ClientRolesReference>,
+    #[serde(default)]
+    pub groups: Vec<GroupDescription>,
+    #[serde(default)]
+    pub users: Vec<UserDescription>,
+}
+
+#[derive(Deserialize)]
+pub struct SmtpServerDescription {
+    pub host: String,
+    #[serde(default = "smtp_port")]
+    pub port: u16,
+    pub from: String,
+}
+
+#[derive(Deserialize)]
+pub struct ClientDescription {
+    pub client_id: String,
+    pub secret: Option<String>,
+    pub secret_env: Option<String>,
+    #[serde(default = "enabled")]
+    pub public_client: bool,
+    #[serde(default = "enabled")]
+    pub direct_access_grants_enabled: bool,
+    #[serde(default)]
+    pub service_accounts_enabled: bool,
+    #[serde(default)]
+    pub roles: Vec<RoleDescription>,
+}
+
+#[derive(Deserialize)]
+pub struct RoleDescription {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub composites: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ClientRolesReference {
+    pub client: String,
+    pub roles: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GroupDescription {
+    pub name: String,
+    #[serde(default)]
+    pub client_roles: Vec<ClientRolesReference>,
+}
+
+#[derive(Deserialize)]
+pub struct UserDescription {
+    pub username: String,
+    pub email: Option<String>,
+    pub password: String,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub client_roles: Vec<ClientRolesReference>,
+}
+
+impl RealmDescription {
+    pub fn load(path: &str, source: &ConfigSource) -> Result<Self, AppErr> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| AppErr::from_owned(format!("failed to read {path}: {err}")))?;
+
+        let is_json = Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+        let mut description: RealmDescription = if is_json {
+            serde_json::from_str(&content)
+                .map_err(|err| AppErr::from_owned(format!("failed to parse {path}: {err}")))?
+        } else {
+            serde_yaml_ng::from_str(&content)
+                .map_err(|err| AppErr::from_owned(format!("failed to parse {path}: {err}")))?
+        };
+
+        description.resolve_secrets(source)?;
+
+        Ok(description)
+    }
+
+    // secrets named by secret_env are read like any other config value, so *_FILE works too
+    fn resolve_secrets(&mut self, source: &ConfigSource) -> Result<(), AppErr> {
+        let secrets = source.read_with(|reader| {
+            self.clients
+                .iter()
+                .map(|client| match (&client.secret, &client.secret_env) {
+                    (None, Some(secret_env)) => Some(reader.required(secret_env)),
+                    (secret, _) => secret.clone(),
+                })
+                .collect::<Vec<_>>()
+        })?;
+
+        for (client, secret) in self.clients.iter_mut().zip(secrets) {
+            client.secret = secret;
+        }
+
+        Ok(())
+    }
+
+    // roles the service relies on are added to the client unless the realm already defines them
+    pub fn merge_client_roles(
+        &mut self,
+        client_id: &str,
+        roles: Vec<RoleDescription>,
+    ) -> Result<(), AppErr> {
+        let realm = &self.realm;
+        let client = self
+            .clients
+            .iter_mut()
+            .find(|client| client.client_id == client_id)
+            .ok_or(AppErr::from_owned(format!(
+                "realm {realm} has no client {client_id}"
+            )))?;
+
+        for role in roles {
+            if !client.roles.iter().any(|x| x.name == role.name) {
+                client.roles.push(role);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ClientDescription {
+    pub fn get_secret(&self) -> String {
+        self.secret.clone().unwrap_or_default()
+    }
+}
+
+impl From<KeycloakSeedingArguments> for RealmDescription {
+    fn from(args: KeycloakSeedingArguments) -> Self {
+        RealmDescription {
+            realm: args.realm_name,
+            verify_email: false,
+            smtp_server: None,
+            clients: vec![ClientDescription {
+                client_id: args.client_name,
+                secret: Some(args.client_secret),
+                secret_env: None,
+                public_client: args.public_client,
+                direct_access_grants_enabled: true,
+                service_accounts_enabled: false,
+                roles: vec![
+                    RoleDescription {
+                        name: args.customer_role_name,
+                        description: args.customer_role_description,
+                        composites: vec![],
+                    },
+                    RoleDescription {
+                        name: args.vendor_role_name,
+                        description: args.vendor_role_description,
+                        composites: vec![],
+                    },
+                ],
+            }],
+            default_roles: vec![],
+            groups: vec![],
+            users: vec![],
+        }
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn smtp_port() -> u16 {
+    25
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use utils::config::ConfigSource;
+
+    use super::{RealmDescription, RoleDescription};
+
+    const REALM: &str = "realm: store
+clients:
+  - client_id: app_client
+    secret_env: APP_CLIENT_SECRET
+    roles:
+      - name: customer
+  - client_id: goods_service
+    secret: inline
+";
+
+    fn load(name: &str, env: &[(&str, &str)]) -> Result<RealmDescription, String> {
+        let path = std::env::temp_dir().join(format!("{name}-{0}.yaml", std::process::id()));
+        std::fs::write(&path, REALM).unwrap();
+
+        let source = ConfigSource::new(
+            env.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            HashMap::new(),
+        );
+        let description = RealmDescription::load(path.to_str().unwrap(), &source);
+        std::fs::remove_file(&path).unwrap();
+
+        description.map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn resolves_client_secrets_through_config() {
+        let secret_path =
+            std::env::temp_dir().join(format!("realm-secret-{0}", std::process::id()));
+        std::fs::write(&secret_path, "s3cret\n").unwrap();
+
+        let description = load(
+            "realm-secret-file",
+            &[("APP_CLIENT_SECRET_FILE", secret_path.to_str().unwrap())],
+        );
+        std::fs::remove_file(&secret_path).unwrap();
+        let description = description.unwrap();
+
+        assert_eq!(description.clients[0].get_secret(), "s3cret");
+        assert_eq!(description.clients[1].get_secret(), "inline");
+    }
+
+    #[test]
+    fn reports_missing_client_secret() {
+        let err = load("realm-missing-secret", &[]).err().unwrap();
+
+        assert!(err.contains("APP_CLIENT_SECRET is missing"));
+    }
+
+    #[test]
+    fn merges_missing_roles_into_configured_client() {
+        let mut description = load("realm-names", &[("APP_CLIENT_SECRET", "s3cret")]).unwrap();
+
+        description
+            .merge_client_roles("app_client", vec![role("customer"), role("vendor")])
+            .unwrap();
+
+        let roles: Vec<(&str, &str)> = description.clients[0]
+            .roles
+            .iter()
+            .map(|x| (x.name.as_str(), x.description.as_str()))
+            .collect();
+        assert_eq!(roles, [("customer", ""), ("vendor", "store vendor")]);
+
+        let err = description
+            .merge_client_roles("store_client", vec![role("customer")])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("has no client store_client"));
+    }
+
+    fn role(name: &str) -> RoleDescription {
+        RoleDescription {
+            name: name.to_owned(),
+            description: format!("store {name}"),
+            composites: vec![],
+        }
+    }
+}
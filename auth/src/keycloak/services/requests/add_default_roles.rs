@@ -0,0 +1,17 @@
+use std::fmt::Display;
+
+use super::assign_roles::AssignRoleRequest;
+
+pub struct AddDefaultRolesRequest {
+    pub realm: String,
+    pub roles: Vec<AssignRoleRequest>,
+}
+
+impl AddDefaultRolesRequest {
+    pub fn new(realm: &impl Display, roles: &[AssignRoleRequest]) -> Self {
+        AddDefaultRolesRequest {
+            realm: realm.to_string(),
+            roles: roles.to_vec(),
+        }
+    }
+}
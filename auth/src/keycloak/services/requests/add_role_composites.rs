@@ -0,0 +1,26 @@
+use std::fmt::Display;
+
+use super::assign_roles::AssignRoleRequest;
+
+pub struct AddRoleCompositesRequest {
+    pub realm: String,
+    pub client_uuid: String,
+    pub role_name: String,
+    pub composites: Vec<AssignRoleRequest>,
+}
+
+impl AddRoleCompositesRequest {
+    pub fn new(
+        realm: &impl Display,
+        client_uuid: &impl Display,
+        role_name: &impl Display,
+        composites: &[AssignRoleRequest],
+    ) -> Self {
+        AddRoleCompositesRequest {
+            realm: realm.to_string(),
+            client_uuid: client_uuid.to_string(),
+            role_name: role_name.to_string(),
+            composites: composites.to_vec(),
+        }
+    }
+}
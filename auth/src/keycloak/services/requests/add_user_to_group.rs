@@ -0,0 +1,23 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct AddUserToGroupRequest {
+    #[serde(skip)]
+    pub realm: String,
+    #[serde(skip)]
+    pub user_uuid: String,
+    #[serde(skip)]
+    pub group_uuid: String,
+}
+
+impl AddUserToGroupRequest {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display, group_uuid: &impl Display) -> Self {
+        AddUserToGroupRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+            group_uuid: group_uuid.to_string(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::fmt::Display;
+
+use super::assign_roles::AssignRoleRequest;
+
+pub struct AssignGroupRolesRequest {
+    pub realm: String,
+    pub group_uuid: String,
+    pub client_uuid: String,
+    pub assign_roles: Vec<AssignRoleRequest>,
+}
+
+impl AssignGroupRolesRequest {
+    pub fn new(
+        realm: &impl Display,
+        group_uuid: &impl Display,
+        client_uuid: &impl Display,
+        assign_roles: &[AssignRoleRequest],
+    ) -> Self {
+        AssignGroupRolesRequest {
+            realm: realm.to_string(),
+            group_uuid: group_uuid.to_string(),
+            client_uuid: client_uuid.to_string(),
+            assign_roles: assign_roles.to_vec(),
+        }
+    }
+}
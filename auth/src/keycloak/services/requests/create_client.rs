@@ -14,6 +14,8 @@ pub struct CreateClientRequest {
     pub secret: String,
     #[serde(rename = "directAccessGrantsEnabled")]
     pub direct_access_grants_enabled: bool,
+    #[serde(rename = "serviceAccountsEnabled")]
+    pub service_accounts_enabled: bool,
 }
 
 impl CreateClientRequest {
@@ -22,9 +24,10 @@ impl CreateClientRequest {
             realm: realm.to_string(),
             client_id: client.to_string(),
             enabled: true,
-            public_client: true,
+            public_client: secret.to_string().is_empty(),
             secret: secret.to_string(),
             direct_access_grants_enabled: true,
+            service_accounts_enabled: false,
         }
     }
 }
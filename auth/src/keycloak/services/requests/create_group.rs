@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct CreateGroupRequest {
+    #[serde(skip)]
+    pub realm: String,
+    pub name: String,
+}
+
+impl CreateGroupRequest {
+    pub fn new(realm: &impl Display, name: &impl Display) -> Self {
+        CreateGroupRequest {
+            realm: realm.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
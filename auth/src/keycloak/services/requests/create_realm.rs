@@ -6,6 +6,17 @@ use serde::Serialize;
 pub struct CreateRealmRequest {
     pub realm: String,
     pub enabled: bool,
+    #[serde(rename = "verifyEmail")]
+    pub verify_email: bool,
+    #[serde(rename = "smtpServer", skip_serializing_if = "Option::is_none")]
+    pub smtp_server: Option<SmtpServerRequest>,
+}
+
+#[derive(Serialize, Clone, PartialEq)]
+pub struct SmtpServerRequest {
+    pub host: String,
+    pub port: String,
+    pub from: String,
 }
 
 impl CreateRealmRequest {
@@ -13,6 +24,18 @@ impl CreateRealmRequest {
         CreateRealmRequest {
             realm: realm.to_string(),
             enabled: true,
+            verify_email: false,
+            smtp_server: None,
+        }
+    }
+}
+
+impl SmtpServerRequest {
+    pub fn new(host: &impl Display, port: u16, from: &impl Display) -> Self {
+        SmtpServerRequest {
+            host: host.to_string(),
+            port: port.to_string(),
+            from: from.to_string(),
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display};
 
 use serde::Serialize;
 
@@ -12,6 +12,10 @@ pub struct CreateUserRequest {
     #[serde(rename = "lastName")]
     pub last_name: String,
     pub enabled: bool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, Vec<String>>,
+    #[serde(rename = "requiredActions", skip_serializing_if = "Vec::is_empty")]
+    pub required_actions: Vec<String>,
     pub credentials: [CreateUserCredentialsRequest; 1],
 }
 
@@ -23,6 +27,8 @@ impl CreateUserRequest {
             first_name: username.to_string(),
             last_name: username.to_string(),
             enabled: true,
+            attributes: BTreeMap::new(),
+            required_actions: vec![],
             credentials: [CreateUserCredentialsRequest::new(password)],
         }
     }
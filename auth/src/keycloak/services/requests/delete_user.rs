@@ -0,0 +1,15 @@
+use std::fmt::Display;
+
+pub struct DeleteUserRequest {
+    pub realm: String,
+    pub user_uuid: String,
+}
+
+impl DeleteUserRequest {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display) -> Self {
+        DeleteUserRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt::Display;
+
+pub const VERIFY_EMAIL_ACTION: &str = "VERIFY_EMAIL";
+
+pub struct ExecuteActionsEmailRequest {
+    pub realm: String,
+    pub user_uuid: String,
+    pub actions: Vec<String>,
+}
+
+impl ExecuteActionsEmailRequest {
+    pub fn new_update_password(realm: &impl Display, user_uuid: &impl Display) -> Self {
+        ExecuteActionsEmailRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+            actions: vec!["UPDATE_PASSWORD".to_owned()],
+        }
+    }
+
+    pub fn new_verify_email(realm: &impl Display, user_uuid: &impl Display) -> Self {
+        ExecuteActionsEmailRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+            actions: vec![VERIFY_EMAIL_ACTION.to_owned()],
+        }
+    }
+}
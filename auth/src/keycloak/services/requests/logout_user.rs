@@ -0,0 +1,20 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct LogoutUserRequest {
+    #[serde(skip)]
+    pub realm: String,
+    #[serde(skip)]
+    pub user_uuid: String,
+}
+
+impl LogoutUserRequest {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display) -> Self {
+        LogoutUserRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+        }
+    }
+}
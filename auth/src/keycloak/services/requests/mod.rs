@@ -3,4 +3,16 @@ pub mod create_client;
 pub mod create_user;
 pub mod create_role;
 pub mod assign_roles;
-pub mod update_users_email_request;
\ No newline at end of file
+pub mod update_users_email_request;
+pub mod delete_user;
+pub mod update_realm;
+pub mod update_client;
+pub mod update_role;
+pub mod add_role_composites;
+pub mod add_default_roles;
+pub mod create_group;
+pub mod assign_group_roles;
+pub mod add_user_to_group;
+pub mod execute_actions_email;
+pub mod reset_password;
+pub mod logout_user;
\ No newline at end of file
@@ -0,0 +1,27 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct ResetPasswordRequest {
+    #[serde(skip)]
+    pub realm: String,
+    #[serde(skip)]
+    pub user_uuid: String,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub value: String,
+    pub temporary: bool,
+}
+
+impl ResetPasswordRequest {
+    pub fn new(realm: &impl Display, user_uuid: &impl Display, password: &impl Display) -> Self {
+        ResetPasswordRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+            credential_type: "password".to_owned(),
+            value: password.to_string(),
+            temporary: false,
+        }
+    }
+}
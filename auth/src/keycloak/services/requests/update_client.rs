@@ -0,0 +1,38 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use super::create_client::CreateClientRequest;
+
+#[derive(Serialize)]
+pub struct UpdateClientRequest {
+    #[serde(skip)]
+    pub realm: String,
+    #[serde(skip)]
+    pub client_uuid: String,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub enabled: bool,
+    #[serde(rename = "publicClient")]
+    pub public_client: bool,
+    pub secret: String,
+    #[serde(rename = "directAccessGrantsEnabled")]
+    pub direct_access_grants_enabled: bool,
+    #[serde(rename = "serviceAccountsEnabled")]
+    pub service_accounts_enabled: bool,
+}
+
+impl UpdateClientRequest {
+    pub fn new(client_uuid: &impl Display, desired: &CreateClientRequest) -> Self {
+        UpdateClientRequest {
+            realm: desired.realm.clone(),
+            client_uuid: client_uuid.to_string(),
+            client_id: desired.client_id.clone(),
+            enabled: desired.enabled,
+            public_client: desired.public_client,
+            secret: desired.secret.clone(),
+            direct_access_grants_enabled: desired.direct_access_grants_enabled,
+            service_accounts_enabled: desired.service_accounts_enabled,
+        }
+    }
+}
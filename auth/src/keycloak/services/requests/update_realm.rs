@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use super::create_realm::{CreateRealmRequest, SmtpServerRequest};
+
+#[derive(Serialize)]
+pub struct UpdateRealmRequest {
+    #[serde(skip)]
+    pub realm: String,
+    pub enabled: bool,
+    #[serde(rename = "verifyEmail")]
+    pub verify_email: bool,
+    #[serde(rename = "smtpServer", skip_serializing_if = "Option::is_none")]
+    pub smtp_server: Option<SmtpServerRequest>,
+}
+
+impl UpdateRealmRequest {
+    pub fn new(desired: &CreateRealmRequest) -> Self {
+        UpdateRealmRequest {
+            realm: desired.realm.clone(),
+            enabled: desired.enabled,
+            verify_email: desired.verify_email,
+            smtp_server: desired.smtp_server.clone(),
+        }
+    }
+}
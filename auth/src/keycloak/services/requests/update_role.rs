@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct UpdateRoleRequest {
+    #[serde(skip)]
+    pub realm: String,
+    #[serde(skip)]
+    pub client_uuid: String,
+    pub name: String,
+    pub description: String,
+}
+
+impl UpdateRoleRequest {
+    pub fn new(
+        realm: &impl Display,
+        client_uuid: &impl Display,
+        name: &impl Display,
+        description: &impl Display,
+    ) -> Self {
+        UpdateRoleRequest {
+            realm: realm.to_string(),
+            client_uuid: client_uuid.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+}
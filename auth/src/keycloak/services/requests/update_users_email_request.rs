@@ -26,4 +26,17 @@ impl UpdateUsersEmailRequest {
             email_verified: true,
         }
     }
+
+    pub fn new_unverified(
+        realm: &impl Display,
+        user_uuid: &impl Display,
+        email: &impl Display,
+    ) -> Self {
+        UpdateUsersEmailRequest {
+            realm: realm.to_string(),
+            user_uuid: user_uuid.to_string(),
+            email: email.to_string(),
+            email_verified: false,
+        }
+    }
 }
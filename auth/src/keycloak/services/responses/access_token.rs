@@ -2,7 +2,13 @@ use derive_more::Display;
 use serde::Deserialize;
 
 
-#[derive(Deserialize, Display)]
+#[derive(Deserialize, Display, Clone)]
+#[display("{access_token}")]
 pub struct AccessTokenResponse {
     pub access_token: String,
+    #[serde(default)]
+    pub expires_in: u64,
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub refresh_expires_in: u64,
 }
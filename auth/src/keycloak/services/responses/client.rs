@@ -5,4 +5,13 @@ pub struct ClientResponse {
     pub id: String,
     #[serde(rename = "clientId")]
     pub client_id: String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(rename = "publicClient", default)]
+    pub public_client: bool,
+    pub secret: Option<String>,
+    #[serde(rename = "directAccessGrantsEnabled", default)]
+    pub direct_access_grants_enabled: bool,
+    #[serde(rename = "serviceAccountsEnabled", default)]
+    pub service_accounts_enabled: bool,
 }
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GroupResponse {
+    pub id: String,
+    pub name: String,
+}
@@ -1,4 +1,8 @@
 pub mod user;
 pub mod client;
 pub mod role;
-pub mod access_token;
\ No newline at end of file
+pub mod access_token;
+pub mod realm;
+pub mod group;pub mod oauth_error;
+
+pub mod user_session;
\ No newline at end of file
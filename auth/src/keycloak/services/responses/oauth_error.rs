@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+pub const ACCOUNT_NOT_SET_UP: &str = "Account is not fully set up";
+
+#[derive(Deserialize)]
+pub struct OAuthErrorResponse {
+    pub error: String,
+    #[serde(default)]
+    pub error_description: String,
+}
+
+pub const INVALID_GRANT: &str = "invalid_grant";
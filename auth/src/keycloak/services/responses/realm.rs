@@ -0,0 +1,13 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct RealmResponse {
+    pub realm: String,
+    pub enabled: bool,
+    #[serde(rename = "verifyEmail", default)]
+    pub verify_email: bool,
+    #[serde(rename = "smtpServer", default)]
+    pub smtp_server: HashMap<String, String>,
+}
@@ -4,4 +4,5 @@ use serde::Deserialize;
 pub struct RoleResponse {
     pub id: String,
     pub name: String,
+    pub description: Option<String>,
 }
@@ -5,4 +5,11 @@ pub struct UserResponse {
     pub id: String,
     pub username: String,
     pub enabled: bool,
+    pub email: Option<String>,
+    #[serde(rename = "emailVerified", default)]
+    pub email_verified: bool,
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
 }
@@ -0,0 +1,16 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct UserSessionResponse {
+    pub id: String,
+    #[serde(rename = "ipAddress", default)]
+    pub ip_address: Option<String>,
+    #[serde(default)]
+    pub start: i64,
+    #[serde(rename = "lastAccess", default)]
+    pub last_access: i64,
+    #[serde(default)]
+    pub clients: HashMap<String, String>,
+}
@@ -54,6 +54,115 @@ pub trait AdminRoutes {
         realm: &(impl Display + Send + Sync),
         user_uuid: &(impl Display + Send + Sync),
     ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_delete_user_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_realm_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_update_realm_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_update_client_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_client_roles_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_update_role_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+        role_name: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_role_composites_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+        role_name: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_default_roles_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_groups_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        name: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_create_group_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_assign_group_roles_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        group_uuid: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_add_user_to_group_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+        group_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_execute_actions_email_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_reset_password_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_user_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_user_client_roles_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_user_sessions_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_logout_user_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
 }
 
 pub trait Routes {
@@ -61,4 +170,9 @@ pub trait Routes {
         &self,
         realm: &(impl Display + Send + Sync),
     ) -> impl Future<Output = Result<String, AppErr>> + Send;
+
+    fn get_logout_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
 }
@@ -1,6 +1,7 @@
 use std::{fmt::Display, sync::Arc};
 
 use futures::TryFutureExt;
+use reqwest::Url;
 use utils::errors::AppErr;
 
 use super::{
@@ -61,10 +62,14 @@ where
     ) -> Result<String, AppErr> {
         let host = self.provider.get_host().await?;
 
-        Ok(format!(
-            "{0}/admin/realms/{1}/users?username={2}",
-            host, realm, username
-        ))
+        let mut url = Url::parse(&format!("{0}/admin/realms/{1}/users", host, realm))
+            .map_err(|err| AppErr::from_owned(format!("invalid users route: {err}")))?;
+
+        url.query_pairs_mut()
+            .append_pair("username", &username.to_string())
+            .append_pair("exact", "true");
+
+        Ok(url.into())
     }
 
     async fn get_clients_query_route(
@@ -74,10 +79,13 @@ where
     ) -> Result<String, AppErr> {
         let host = self.provider.get_host().await?;
 
-        Ok(format!(
-            "{0}/admin/realms/{1}/clients?clientId={2}",
-            host, realm, client_id
-        ))
+        let mut url = Url::parse(&format!("{0}/admin/realms/{1}/clients", host, realm))
+            .map_err(|err| AppErr::from_owned(format!("invalid clients route: {err}")))?;
+
+        url.query_pairs_mut()
+            .append_pair("clientId", &client_id.to_string());
+
+        Ok(url.into())
     }
 
     async fn get_create_role_route(
@@ -101,10 +109,7 @@ where
     ) -> Result<String, AppErr> {
         let host = self.provider.get_host().await?;
 
-        Ok(format!(
-            "{0}/admin/realms/{1}/clients/{2}/roles/{3}",
-            host, realm, client_uuid, role_name
-        ))
+        client_role_route(&host, realm, client_uuid, role_name, &[])
     }
 
     async fn get_assign_roles_query_route(
@@ -133,6 +138,232 @@ where
             host, realm, user_uuid
         ))
     }
+
+    async fn get_delete_user_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}",
+            host, realm, user_uuid
+        ))
+    }
+
+    async fn get_realm_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!("{0}/admin/realms/{1}", host, realm))
+    }
+
+    async fn get_update_realm_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!("{0}/admin/realms/{1}", host, realm))
+    }
+
+    async fn get_update_client_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/clients/{2}",
+            host, realm, client_uuid
+        ))
+    }
+
+    async fn get_client_roles_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/clients/{2}/roles",
+            host, realm, client_uuid
+        ))
+    }
+
+    async fn get_update_role_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+        role_name: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        client_role_route(&host, realm, client_uuid, role_name, &[])
+    }
+
+    async fn get_role_composites_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+        role_name: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        client_role_route(&host, realm, client_uuid, role_name, &["composites"])
+    }
+
+    async fn get_default_roles_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/roles/default-roles-{2}/composites",
+            host,
+            realm,
+            realm.to_string().to_lowercase()
+        ))
+    }
+
+    async fn get_groups_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        name: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        let mut url = Url::parse(&format!("{0}/admin/realms/{1}/groups", host, realm))
+            .map_err(|err| AppErr::from_owned(format!("invalid groups route: {err}")))?;
+
+        url.query_pairs_mut()
+            .append_pair("search", &name.to_string())
+            .append_pair("exact", "true");
+
+        Ok(url.into())
+    }
+
+    async fn get_create_group_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!("{0}/admin/realms/{1}/groups", host, realm))
+    }
+
+    async fn get_assign_group_roles_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        group_uuid: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/groups/{2}/role-mappings/clients/{3}",
+            host, realm, group_uuid, client_uuid
+        ))
+    }
+
+    async fn get_add_user_to_group_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+        group_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}/groups/{3}",
+            host, realm, user_uuid, group_uuid
+        ))
+    }
+
+    async fn get_execute_actions_email_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}/execute-actions-email",
+            host, realm, user_uuid
+        ))
+    }
+
+    async fn get_reset_password_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}/reset-password",
+            host, realm, user_uuid
+        ))
+    }
+
+    async fn get_user_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}",
+            host, realm, user_uuid
+        ))
+    }
+
+    async fn get_user_client_roles_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+        client_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}/role-mappings/clients/{3}/composite",
+            host, realm, user_uuid, client_uuid
+        ))
+    }
+
+    async fn get_user_sessions_query_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}/sessions",
+            host, realm, user_uuid
+        ))
+    }
+
+    async fn get_logout_user_route(
+        &self,
+        realm: &(impl Display + Send + Sync),
+        user_uuid: &(impl Display + Send + Sync),
+    ) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{0}/admin/realms/{1}/users/{2}/logout",
+            host, realm, user_uuid
+        ))
+    }
 }
 
 pub struct DefaultRoutes<THost: HostAddressProvider> {
@@ -156,4 +387,86 @@ where
             "{host}/realms/{realm}/protocol/openid-connect/token"
         ))
     }
+
+    async fn get_logout_route(&self, realm: &impl Display) -> Result<String, AppErr> {
+        let host = self.provider.get_host().await?;
+
+        Ok(format!(
+            "{host}/realms/{realm}/protocol/openid-connect/logout"
+        ))
+    }
+}
+
+fn client_role_route(
+    host: &str,
+    realm: &impl Display,
+    client_uuid: &impl Display,
+    role_name: &impl Display,
+    rest: &[&str],
+) -> Result<String, AppErr> {
+    let mut url = Url::parse(&format!(
+        "{0}/admin/realms/{1}/clients/{2}/roles",
+        host, realm, client_uuid
+    ))
+    .map_err(|err| AppErr::from_owned(format!("invalid roles route: {err}")))?;
+
+    url.path_segments_mut()
+        .map_err(|_| AppErr::from("invalid roles route"))?
+        .push(&role_name.to_string())
+        .extend(rest);
+
+    Ok(url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::keycloak::services::{
+        host_implementation::ConfiguredHostAddressProvider, routes::AdminRoutes,
+    };
+
+    use super::DefaultAdminRoutes;
+
+    fn routes() -> DefaultAdminRoutes<ConfiguredHostAddressProvider> {
+        DefaultAdminRoutes::new(Arc::new(ConfiguredHostAddressProvider::new(
+            &"http://keycloak:8080",
+        )))
+    }
+
+    #[tokio::test]
+    async fn encodes_role_names_as_path_segments() {
+        let routes = routes();
+
+        let role = routes
+            .get_role_query_route(&"store", &"client-1", &"shop owner/admin")
+            .await
+            .unwrap();
+        let composites = routes
+            .get_role_composites_route(&"store", &"client-1", &"shop owner/admin")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            role,
+            "http://keycloak:8080/admin/realms/store/clients/client-1/roles/shop%20owner%2Fadmin"
+        );
+        assert_eq!(
+            composites,
+            "http://keycloak:8080/admin/realms/store/clients/client-1/roles/shop%20owner%2Fadmin/composites"
+        );
+    }
+
+    #[tokio::test]
+    async fn encodes_group_names_in_query() {
+        let route = routes()
+            .get_groups_query_route(&"store", &"sales & support")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            route,
+            "http://keycloak:8080/admin/realms/store/groups?search=sales+%26+support&exact=true"
+        );
+    }
 }
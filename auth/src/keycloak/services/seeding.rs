@@ -1,11 +1,16 @@
 use utils::errors::AppErr;
 
+use super::realm_description::RealmDescription;
+
 pub struct KeycloakSeedingArguments {
     pub realm_name: String,
     pub client_name: String,
+    pub public_client: bool,
     pub client_secret: String,
     pub customer_role_name: String,
+    pub customer_role_description: String,
     pub vendor_role_name: String,
+    pub vendor_role_description: String,
 }
 
 impl KeycloakSeedingArguments {
@@ -14,14 +19,19 @@ impl KeycloakSeedingArguments {
         client_name: &str,
         client_secret: &str,
         customer_role_name: &str,
+        customer_role_description: &str,
         vendor_role_name: &str,
+        vendor_role_description: &str,
     ) -> Self {
         KeycloakSeedingArguments {
             realm_name: realm_name.to_string(),
             client_name: client_name.to_string(),
+            public_client: client_secret.is_empty(),
             client_secret: client_secret.to_string(),
             customer_role_name: customer_role_name.to_string(),
+            customer_role_description: customer_role_description.to_string(),
             vendor_role_name: vendor_role_name.to_string(),
+            vendor_role_description: vendor_role_description.to_string(),
         }
     }
 }
@@ -29,6 +39,6 @@ impl KeycloakSeedingArguments {
 pub trait KeycloakSeeding {
     fn seed(
         &self,
-        args: KeycloakSeedingArguments,
+        description: RealmDescription,
     ) -> impl Future<Output = Result<(), AppErr>> + Send;
 }
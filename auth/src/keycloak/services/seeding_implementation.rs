@@ -1,19 +1,81 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use utils::errors::AppErr;
 
 use crate::keycloak::services::{
-    queries::clients::ClientsQuery,
+    queries::{
+        client_roles::ClientRolesQuery, clients::ClientsQuery, groups::GroupsQuery,
+        realm::RealmQuery, users::UsersQuery,
+    },
+    realm_description::{
+        ClientDescription, ClientRolesReference, GroupDescription, RealmDescription,
+        UserDescription,
+    },
     requests::{
-        create_client::CreateClientRequest, create_realm::CreateRealmRequest,
+        add_default_roles::AddDefaultRolesRequest,
+        add_role_composites::AddRoleCompositesRequest,
+        add_user_to_group::AddUserToGroupRequest,
+        assign_group_roles::AssignGroupRolesRequest,
+        assign_roles::{AssignRoleRequest, AssignRolesRequest},
+        create_client::CreateClientRequest,
+        create_group::CreateGroupRequest,
+        create_realm::{CreateRealmRequest, SmtpServerRequest},
         create_role::CreateRoleRequest,
+        create_user::CreateUserRequest,
+        update_client::UpdateClientRequest,
+        update_realm::UpdateRealmRequest,
+        update_role::UpdateRoleRequest,
+        update_users_email_request::UpdateUsersEmailRequest,
+    },
+    responses::{
+        client::ClientResponse, group::GroupResponse, realm::RealmResponse, role::RoleResponse,
     },
 };
 
-use super::{
-    management::KeycloakManagement,
-    seeding::{KeycloakSeeding, KeycloakSeedingArguments},
-};
+use super::{management::KeycloakManagement, seeding::KeycloakSeeding};
+
+#[derive(Default)]
+struct SeedingSummary {
+    created: Vec<String>,
+    updated: Vec<String>,
+    unchanged: Vec<String>,
+}
+
+impl SeedingSummary {
+    fn log(&self) {
+        log::info!(
+            "seeding finished, created: [{0}], updated: [{1}], unchanged: [{2}]",
+            self.created.join(", "),
+            self.updated.join(", "),
+            self.unchanged.join(", ")
+        );
+    }
+}
+
+struct SeededClient {
+    client: ClientResponse,
+    roles: Vec<RoleResponse>,
+}
+
+impl SeededClient {
+    fn resolve_roles(&self, names: &[String]) -> Result<Vec<AssignRoleRequest>, AppErr> {
+        names
+            .iter()
+            .map(|name| {
+                self.roles
+                    .iter()
+                    .find(|role| role.name == *name)
+                    .map(|role| AssignRoleRequest::new(&role.id, &role.name))
+                    .ok_or_else(|| {
+                        AppErr::from_owned(format!(
+                            "role {name} is not defined for client {0}",
+                            self.client.client_id
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
 
 pub struct DefaultKeycloakSeeding<TManager: KeycloakManagement> {
     manager: Arc<TManager>,
@@ -25,61 +87,639 @@ impl<TManager: KeycloakManagement> DefaultKeycloakSeeding<TManager> {
     }
 }
 
-impl<TManager> KeycloakSeeding for DefaultKeycloakSeeding<TManager>
+impl<TManager> DefaultKeycloakSeeding<TManager>
 where
     TManager: KeycloakManagement + Send + Sync,
 {
-    async fn seed(&self, args: KeycloakSeedingArguments) -> Result<(), AppErr> {
-        self.manager
-            .create_realm(&CreateRealmRequest::new(&args.realm_name))
-            .await?;
+    async fn seed_realm(
+        &self,
+        description: &RealmDescription,
+        summary: &mut SeedingSummary,
+    ) -> Result<(), AppErr> {
+        let entry = format!("realm {0}", description.realm);
 
-        log::info!("realm created");
+        let mut desired = CreateRealmRequest::new(&description.realm);
+        desired.verify_email = description.verify_email;
+        desired.smtp_server = description
+            .smtp_server
+            .as_ref()
+            .map(|smtp| SmtpServerRequest::new(&smtp.host, smtp.port, &smtp.from));
+
+        match self
+            .manager
+            .query_realm(&RealmQuery::new(&description.realm))
+            .await?
+        {
+            None => {
+                self.manager.create_realm(&desired).await?;
+                summary.created.push(entry);
+            }
+            Some(realm) if realm_has_drifted(&realm, &desired) => {
+                self.manager
+                    .update_realm(&UpdateRealmRequest::new(&desired))
+                    .await?;
+                summary.updated.push(entry);
+            }
+            Some(_) => summary.unchanged.push(entry),
+        }
 
-        self.manager
-            .create_client(&CreateClientRequest::new(
-                &args.client_name,
-                &args.realm_name,
-                &args.client_secret,
-            ))
+        Ok(())
+    }
+
+    async fn seed_client(
+        &self,
+        realm_name: &str,
+        description: &ClientDescription,
+        summary: &mut SeedingSummary,
+    ) -> Result<SeededClient, AppErr> {
+        let entry = format!("client {0}", description.client_id);
+
+        let mut desired = CreateClientRequest::new(
+            &description.client_id,
+            &realm_name,
+            &description.get_secret(),
+        );
+        desired.public_client = description.public_client;
+        desired.direct_access_grants_enabled = description.direct_access_grants_enabled;
+        desired.service_accounts_enabled = description.service_accounts_enabled;
+
+        match self.query_client(&desired).await? {
+            None => {
+                self.manager.create_client(&desired).await?;
+                summary.created.push(entry);
+            }
+            Some(client) if has_drifted(&client, &desired) => {
+                self.manager
+                    .update_client(&UpdateClientRequest::new(&client.id, &desired))
+                    .await?;
+                summary.updated.push(entry);
+            }
+            Some(_) => summary.unchanged.push(entry),
+        }
+
+        let client = self
+            .query_client(&desired)
+            .await?
+            .ok_or(AppErr::from("cannot get client from payload"))?;
+
+        log::info!("got client: {0}, {1}", client.id, client.client_id);
+
+        self.seed_roles(realm_name, &client, description, summary)
             .await?;
 
-        log::info!("client created");
+        let roles = self
+            .manager
+            .query_client_roles(&ClientRolesQuery::new(&realm_name, &client.id))
+            .await?;
+
+        let seeded = SeededClient { client, roles };
+
+        for role in description
+            .roles
+            .iter()
+            .filter(|role| !role.composites.is_empty())
+        {
+            self.manager
+                .add_role_composites(&AddRoleCompositesRequest::new(
+                    &realm_name,
+                    &seeded.client.id,
+                    &role.name,
+                    &seeded.resolve_roles(&role.composites)?,
+                ))
+                .await?;
+        }
 
+        Ok(seeded)
+    }
+
+    async fn query_client(
+        &self,
+        desired: &CreateClientRequest,
+    ) -> Result<Option<ClientResponse>, AppErr> {
         let clients = self
             .manager
-            .query_clients(&ClientsQuery::new(&args.realm_name, &args.client_name))
+            .query_clients(&ClientsQuery::new(&desired.realm, &desired.client_id))
             .await?;
 
-        let client = clients
-            .get(0)
-            .map(Result::Ok)
-            .unwrap_or(Result::Err(AppErr::from("cannot get client from payload")))?;
+        Ok(clients
+            .into_iter()
+            .find(|client| client.client_id == desired.client_id))
+    }
 
-        log::info!("got client: {0}, {1}", client.id, client.client_id);
+    async fn seed_roles(
+        &self,
+        realm_name: &str,
+        client: &ClientResponse,
+        description: &ClientDescription,
+        summary: &mut SeedingSummary,
+    ) -> Result<(), AppErr> {
+        let existing = self
+            .manager
+            .query_client_roles(&ClientRolesQuery::new(&realm_name, &client.id))
+            .await?;
+
+        for desired in description.roles.iter() {
+            let entry = format!("role {0}/{1}", client.client_id, desired.name);
+            let role = existing.iter().find(|role| role.name == desired.name);
+
+            match role {
+                None => {
+                    self.manager
+                        .create_role(&CreateRoleRequest::new(
+                            &realm_name,
+                            &client.id,
+                            &desired.name,
+                            &desired.description,
+                        ))
+                        .await?;
+                    summary.created.push(entry);
+                }
+                Some(role) if role.description.as_deref().unwrap_or("") != desired.description => {
+                    self.manager
+                        .update_role(&UpdateRoleRequest::new(
+                            &realm_name,
+                            &client.id,
+                            &desired.name,
+                            &desired.description,
+                        ))
+                        .await?;
+                    summary.updated.push(entry);
+                }
+                Some(_) => summary.unchanged.push(entry),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn seed_default_roles(
+        &self,
+        realm_name: &str,
+        references: &[ClientRolesReference],
+        clients: &HashMap<String, SeededClient>,
+    ) -> Result<(), AppErr> {
+        for reference in references.iter() {
+            let client = find_client(clients, &reference.client)?;
+
+            self.manager
+                .add_default_roles(&AddDefaultRolesRequest::new(
+                    &realm_name,
+                    &client.resolve_roles(&reference.roles)?,
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn seed_group(
+        &self,
+        realm_name: &str,
+        description: &GroupDescription,
+        clients: &HashMap<String, SeededClient>,
+        summary: &mut SeedingSummary,
+    ) -> Result<GroupResponse, AppErr> {
+        let entry = format!("group {0}", description.name);
+
+        let group = match self.query_group(realm_name, &description.name).await? {
+            Some(group) => {
+                summary.unchanged.push(entry);
+                group
+            }
+            None => {
+                self.manager
+                    .create_group(&CreateGroupRequest::new(&realm_name, &description.name))
+                    .await?;
+                summary.created.push(entry);
+
+                self.query_group(realm_name, &description.name)
+                    .await?
+                    .ok_or(AppErr::from("cannot get group from payload"))?
+            }
+        };
+
+        for reference in description.client_roles.iter() {
+            let client = find_client(clients, &reference.client)?;
+
+            self.manager
+                .assign_group_roles(&AssignGroupRolesRequest::new(
+                    &realm_name,
+                    &group.id,
+                    &client.client.id,
+                    &client.resolve_roles(&reference.roles)?,
+                ))
+                .await?;
+        }
 
-        self.manager
-            .create_role(&CreateRoleRequest::new(
-                &args.realm_name,
-                &client.id,
-                &args.customer_role_name,
-                &"",
-            ))
+        Ok(group)
+    }
+
+    async fn query_group(
+        &self,
+        realm_name: &str,
+        name: &str,
+    ) -> Result<Option<GroupResponse>, AppErr> {
+        let groups = self
+            .manager
+            .query_groups(&GroupsQuery::new(&realm_name, &name))
             .await?;
 
-        log::info!("customer role created");
+        Ok(groups.into_iter().find(|group| group.name == name))
+    }
+
+    async fn seed_user(
+        &self,
+        realm_name: &str,
+        description: &UserDescription,
+        clients: &HashMap<String, SeededClient>,
+        groups: &HashMap<String, GroupResponse>,
+        summary: &mut SeedingSummary,
+    ) -> Result<(), AppErr> {
+        let entry = format!("user {0}", description.username);
+
+        let user_id = match self.query_user(realm_name, &description.username).await? {
+            Some(user_id) => {
+                summary.unchanged.push(entry);
+                user_id
+            }
+            None => {
+                self.manager
+                    .create_user(&CreateUserRequest::new(
+                        &realm_name,
+                        &description.username,
+                        &description.password,
+                    ))
+                    .await?;
+                summary.created.push(entry);
+
+                let user_id = self
+                    .query_user(realm_name, &description.username)
+                    .await?
+                    .ok_or(AppErr::from("cannot get user from payload"))?;
+
+                if let Some(email) = &description.email {
+                    self.manager
+                        .update_users_email(&UpdateUsersEmailRequest::new_verified(
+                            &realm_name,
+                            &user_id,
+                            email,
+                        ))
+                        .await?;
+                }
+
+                user_id
+            }
+        };
 
-        self.manager
-            .create_role(&CreateRoleRequest::new(
-                &args.realm_name,
-                &client.id,
-                &args.vendor_role_name,
-                &"",
-            ))
+        for reference in description.client_roles.iter() {
+            let client = find_client(clients, &reference.client)?;
+
+            self.manager
+                .assign_roles(&AssignRolesRequest::new(
+                    &realm_name,
+                    &user_id,
+                    &client.client.id,
+                    &client.resolve_roles(&reference.roles)?,
+                ))
+                .await?;
+        }
+
+        for group_name in description.groups.iter() {
+            let group = groups
+                .get(group_name)
+                .ok_or_else(|| AppErr::from_owned(format!("group {group_name} is not defined")))?;
+
+            self.manager
+                .add_user_to_group(&AddUserToGroupRequest::new(
+                    &realm_name,
+                    &user_id,
+                    &group.id,
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn query_user(&self, realm_name: &str, username: &str) -> Result<Option<String>, AppErr> {
+        let users = self
+            .manager
+            .query_users(&UsersQuery::new(&realm_name, &username))
             .await?;
 
-        log::info!("vendor role created");
+        Ok(users
+            .into_iter()
+            .find(|user| user.username.eq_ignore_ascii_case(username))
+            .map(|user| user.id))
+    }
+}
+
+impl<TManager> KeycloakSeeding for DefaultKeycloakSeeding<TManager>
+where
+    TManager: KeycloakManagement + Send + Sync,
+{
+    async fn seed(&self, description: RealmDescription) -> Result<(), AppErr> {
+        let mut summary = SeedingSummary::default();
+        let realm_name = &description.realm;
+
+        self.seed_realm(&description, &mut summary).await?;
+
+        let mut clients = HashMap::new();
+        for client in description.clients.iter() {
+            let seeded = self.seed_client(realm_name, client, &mut summary).await?;
+            clients.insert(client.client_id.clone(), seeded);
+        }
+
+        self.seed_default_roles(realm_name, &description.default_roles, &clients)
+            .await?;
+
+        let mut groups = HashMap::new();
+        for group in description.groups.iter() {
+            let seeded = self
+                .seed_group(realm_name, group, &clients, &mut summary)
+                .await?;
+            groups.insert(group.name.clone(), seeded);
+        }
+
+        for user in description.users.iter() {
+            self.seed_user(realm_name, user, &clients, &groups, &mut summary)
+                .await?;
+        }
+
+        summary.log();
 
         Ok(())
     }
 }
+
+fn find_client<'a>(
+    clients: &'a HashMap<String, SeededClient>,
+    client_id: &str,
+) -> Result<&'a SeededClient, AppErr> {
+    clients
+        .get(client_id)
+        .ok_or_else(|| AppErr::from_owned(format!("client {client_id} is not defined")))
+}
+
+fn realm_has_drifted(realm: &RealmResponse, desired: &CreateRealmRequest) -> bool {
+    let smtp_drifted = desired.smtp_server.as_ref().is_some_and(|smtp| {
+        realm.smtp_server.get("host") != Some(&smtp.host)
+            || realm.smtp_server.get("port") != Some(&smtp.port)
+            || realm.smtp_server.get("from") != Some(&smtp.from)
+    });
+
+    realm.enabled != desired.enabled || realm.verify_email != desired.verify_email || smtp_drifted
+}
+
+fn has_drifted(client: &ClientResponse, desired: &CreateClientRequest) -> bool {
+    let secret_drifted = !desired.public_client
+        && client
+            .secret
+            .as_ref()
+            .is_some_and(|secret| *secret != desired.secret);
+
+    client.enabled != desired.enabled
+        || client.public_client != desired.public_client
+        || client.direct_access_grants_enabled != desired.direct_access_grants_enabled
+        || client.service_accounts_enabled != desired.service_accounts_enabled
+        || secret_drifted
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{Method, StatusCode};
+    use utils::service_token::{
+        ClientCredentialsSettings, ClientCredentialsTokenSource, ServiceTokenSource,
+    };
+
+    use crate::keycloak::{
+        mock::server::{MockKeycloak, TEST_CLIENT, TEST_REALM, test_realm_description},
+        services::{
+            realm_description::{RealmDescription, SmtpServerDescription},
+            seeding::KeycloakSeeding,
+        },
+    };
+
+    use super::DefaultKeycloakSeeding;
+
+    #[tokio::test]
+    async fn seeds_realm_client_and_roles() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        seeder.seed(test_realm_description()).await.unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            assert!(realm.enabled);
+
+            let client = realm.client(TEST_CLIENT).unwrap();
+            assert!(client.public_client);
+            assert!(client.direct_access_grants_enabled);
+
+            let roles: Vec<_> = client
+                .roles
+                .iter()
+                .map(|role| (role.name.as_str(), role.description.as_deref()))
+                .collect();
+            assert_eq!(
+                roles,
+                [
+                    ("customer", Some("store customer")),
+                    ("vendor", Some("store vendor"))
+                ]
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn seeding_twice_creates_nothing_new() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        seeder.seed(test_realm_description()).await.unwrap();
+        let created = keycloak.request_count(Method::POST, "/admin/realms");
+
+        seeder.seed(test_realm_description()).await.unwrap();
+
+        assert_eq!(
+            keycloak.request_count(Method::POST, "/admin/realms"),
+            created
+        );
+        assert_eq!(keycloak.request_count(Method::PUT, "/admin/realms"), 0);
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            assert_eq!(realm.clients.len(), 1);
+            assert_eq!(realm.clients[0].roles.len(), 2);
+        });
+    }
+
+    #[tokio::test]
+    async fn seeding_reconciles_drifted_settings() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        seeder.seed(test_realm_description()).await.unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            realm.enabled = false;
+            realm.clients[0].direct_access_grants_enabled = false;
+            realm.clients[0].roles[0].description = Some("outdated".to_owned());
+        });
+
+        seeder.seed(test_realm_description()).await.unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            assert!(realm.enabled);
+            assert!(realm.clients[0].direct_access_grants_enabled);
+            assert_eq!(
+                realm.clients[0].roles[0].description.as_deref(),
+                Some("store customer")
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn seeds_and_reconciles_realm_email_settings() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        let description = || {
+            let mut description = test_realm_description();
+            description.verify_email = true;
+            description.smtp_server = Some(SmtpServerDescription {
+                host: "mailpit".to_owned(),
+                port: 1025,
+                from: "no-reply@store.local".to_owned(),
+            });
+            description
+        };
+
+        seeder.seed(description()).await.unwrap();
+        seeder.seed(description()).await.unwrap();
+        assert_eq!(keycloak.request_count(Method::PUT, "/admin/realms"), 0);
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            assert!(realm.verify_email);
+            assert_eq!(realm.smtp_server["port"], "1025");
+
+            realm
+                .smtp_server
+                .insert("host".to_owned(), "localhost".to_owned());
+        });
+
+        seeder.seed(description()).await.unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            assert_eq!(realm.smtp_server["host"], "mailpit");
+        });
+    }
+
+    #[tokio::test]
+    async fn seeds_groups_users_and_composite_roles() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        let description: RealmDescription = serde_yaml_ng::from_str(
+            "
+realm: test_realm
+clients:
+  - client_id: app_client
+    roles:
+      - name: customer
+      - name: vendor
+      - name: seller
+        composites: [customer, vendor]
+default_roles:
+  - client: app_client
+    roles: [customer]
+groups:
+  - name: sellers
+    client_roles:
+      - client: app_client
+        roles: [seller]
+users:
+  - username: dev
+    email: dev@example.com
+    password: dev
+    groups: [sellers]
+",
+        )
+        .unwrap();
+
+        seeder.seed(description).await.unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            let user = realm.user("dev").unwrap();
+            assert_eq!(user.email.as_deref(), Some("dev@example.com"));
+            assert!(user.email_verified);
+
+            let mut roles: Vec<_> = realm
+                .user_client_roles(user, &realm.client(TEST_CLIENT).unwrap().id)
+                .into_iter()
+                .map(|role| role.name.as_str())
+                .collect();
+            roles.sort();
+            assert_eq!(roles, ["customer", "seller", "vendor"]);
+        });
+    }
+
+    #[tokio::test]
+    async fn seeding_reports_keycloak_failures() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        keycloak.fail(Method::POST, "/roles", StatusCode::INTERNAL_SERVER_ERROR);
+
+        assert!(seeder.seed(test_realm_description()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn seeds_service_clients_usable_for_client_credentials() {
+        let keycloak = MockKeycloak::start().await;
+        let seeder = DefaultKeycloakSeeding::new(keycloak.create_manager());
+
+        let description: RealmDescription = serde_yaml_ng::from_str(
+            "
+realm: test_realm
+clients:
+  - client_id: goods_service
+    secret: goods_secret
+    public_client: false
+    direct_access_grants_enabled: false
+    service_accounts_enabled: true
+",
+        )
+        .unwrap();
+
+        seeder.seed(description).await.unwrap();
+
+        keycloak.with_data(|data| {
+            let client = data
+                .realm(TEST_REALM)
+                .unwrap()
+                .client("goods_service")
+                .unwrap();
+            assert!(!client.public_client);
+            assert!(client.service_accounts_enabled);
+        });
+
+        let source = ClientCredentialsTokenSource::new(
+            ClientCredentialsSettings {
+                host: keycloak.host().to_owned(),
+                realm: TEST_REALM.to_owned(),
+                client_id: "goods_service".to_owned(),
+                client_secret: "goods_secret".to_owned(),
+            },
+            keycloak.create_client(),
+        );
+
+        let token = source.get_token().await.unwrap();
+        assert_eq!(source.get_token().await.unwrap(), token);
+    }
+}
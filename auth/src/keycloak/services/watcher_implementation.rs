@@ -1,21 +1,27 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
-use tokio::{select, time::sleep};
 use tokio_util::sync::CancellationToken;
-use utils::errors::AppErr;
+use utils::{
+    errors::AppErr,
+    readiness::{ReadinessSettings, wait_until_ready},
+};
 
 use super::{authorization::AdminAccessTokenProvider, watcher::KeycloakWatcher};
 
 pub struct DefaultKeycloakWatcher<TAuthorization: AdminAccessTokenProvider> {
     auth_provider: Arc<TAuthorization>,
+    settings: ReadinessSettings,
 }
 
 impl<TAuthorization> DefaultKeycloakWatcher<TAuthorization>
 where
     TAuthorization: AdminAccessTokenProvider,
 {
-    pub fn new(auth_provider: Arc<TAuthorization>) -> Self {
-        DefaultKeycloakWatcher { auth_provider }
+    pub fn new(auth_provider: Arc<TAuthorization>, settings: ReadinessSettings) -> Self {
+        DefaultKeycloakWatcher {
+            auth_provider,
+            settings,
+        }
     }
 }
 
@@ -24,22 +30,19 @@ where
     TAuthorization: AdminAccessTokenProvider + Send + Sync,
 {
     async fn watch(&self, cancellation_token: &CancellationToken) -> Result<(), AppErr> {
-        loop {
-            log::info!("pinging keycloak service");
-            sleep(Duration::from_secs(3)).await;
+        let auth_provider = &self.auth_provider;
 
-            select! {
-                token = self.auth_provider.get_access_token() => {
-                    if token.inspect_err(|err| log::error!("ping err: {err}")).is_ok() {
-                        log::info!("keycloak is online");
-                        return Ok(());
-                    }
-                    else {
-                        continue
-                    }
-                },
-                _ = cancellation_token.cancelled() => Result::<(), AppErr>::Err(AppErr::from("keycloak is not online"))
-            }?
-        }
+        wait_until_ready(
+            "keycloak",
+            &self.settings,
+            cancellation_token,
+            |attempt_token| async move {
+                auth_provider
+                    .get_access_token_with_cancel(&attempt_token)
+                    .await
+                    .map(|_| ())
+            },
+        )
+        .await
     }
 }
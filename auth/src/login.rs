@@ -1,40 +1,105 @@
-use std::collections::HashMap;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     Json, Router,
+    extract::{ConnectInfo, State},
     response::{IntoResponse, Result},
     routing::post,
 };
 use futures::TryFutureExt;
-use http::StatusCode;
+use http::{HeaderMap, StatusCode};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use utils::{env::env_var, errors::HttpAppErr, http::ResponseExtended};
+use utils::{
+    errors::HttpAppErr,
+    http::ResponseExtended,
+    validation::{ValidJson, Validate, Validator},
+};
 
-use crate::keycloak::{
-    keycloak_ex::KeycloakExtensions, keycloak_factory::create_default_routes,
-    services::routes::Routes,
+use crate::{
+    config::{AuthConfig, KeycloakConfig},
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        keycloak_factory::create_default_routes,
+        services::{
+            responses::oauth_error::{ACCOUNT_NOT_SET_UP, OAuthErrorResponse},
+            routes::Routes,
+        },
+    },
+    rate_limit::{
+        client_ip::client_ip,
+        login_limiter::{LoginAttempt, LoginLimiter, enforce_limit},
+    },
+    tenant::resolve_tenant,
 };
 
-pub fn create_login_router() -> Router {
-    Router::new().route("/api/login", post(login))
+#[derive(Clone)]
+pub struct TokenState {
+    pub client: Arc<Client>,
+    pub config: Arc<AuthConfig>,
 }
 
-async fn login(Json(request): Json<LoginRequest>) -> Result<LoginResponse> {
-    let routes = create_default_routes();
+pub struct LoginState<TLimiter> {
+    pub client: Arc<Client>,
+    pub config: Arc<AuthConfig>,
+    pub limiter: Arc<TLimiter>,
+}
 
-    let auth_url = routes
-        .get_auth_route(&env_var("KEYCLOAK_REALM")?)
-        .log_err()
-        .await?;
+impl<TLimiter> Clone for LoginState<TLimiter> {
+    fn clone(&self) -> Self {
+        LoginState {
+            client: self.client.clone(),
+            config: self.config.clone(),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+pub fn create_login_router<TLimiter>(
+    client: Arc<Client>,
+    config: Arc<AuthConfig>,
+    limiter: Arc<TLimiter>,
+) -> Router
+where
+    TLimiter: LoginLimiter + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/api/login", post(login::<TLimiter>))
+        .with_state(LoginState {
+            client,
+            config,
+            limiter,
+        })
+}
+
+async fn login<TLimiter>(
+    State(state): State<LoginState<TLimiter>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<LoginRequest>,
+) -> Result<LoginResponse>
+where
+    TLimiter: LoginLimiter + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let attempt = LoginAttempt::new(
+        &client_ip(&headers, &peer, &state.config.trusted_proxies),
+        &format!("{0}:{1}", tenant.id, request.login),
+    );
 
-    let mut params = HashMap::new();
-    params.insert("client_id", env_var("KEYCLOAK_CLIENT")?);
+    enforce_limit(state.limiter.as_ref(), &attempt, "login attempts").await?;
+
+    let routes = create_default_routes(&state.config.keycloak);
+
+    let auth_url = routes.get_auth_route(&tenant.realm).log_err().await?;
+
+    let mut params = state.config.keycloak.client_params();
     params.insert("username", request.login);
     params.insert("password", request.password);
     params.insert("grant_type", "password".to_owned());
 
-    let response = Client::new()
+    let response = state
+        .client
         .post(auth_url)
         .form(&params)
         .header("Content-Type", "application/x-www-form-urlencoded")
@@ -43,17 +108,61 @@ async fn login(Json(request): Json<LoginRequest>) -> Result<LoginResponse> {
         .map_err(|_| HttpAppErr::new(StatusCode::FAILED_DEPENDENCY, "keycloak failed"))
         .await?;
 
-    let res = response.ensure_success_json::<LoginResponse>().await?;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        state
+            .limiter
+            .record_failure(&attempt)
+            .await
+            .map_err(|err| HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    }
+
+    let res = response
+        .ensure_success_json::<LoginResponse>()
+        .await
+        .map_err(|err| refuse_incomplete_account(err, &state.config.keycloak))?;
+
+    state
+        .limiter
+        .record_success(&attempt)
+        .await
+        .map_err(|err| HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err))?;
 
     Ok(res)
 }
 
-#[derive(Deserialize)]
+fn refuse_incomplete_account(err: HttpAppErr, config: &KeycloakConfig) -> HttpAppErr {
+    let incomplete = err.status == StatusCode::BAD_REQUEST
+        && serde_json::from_str::<OAuthErrorResponse>(&err.reason)
+            .is_ok_and(|error| error.error_description == ACCOUNT_NOT_SET_UP);
+
+    if !incomplete {
+        return err;
+    }
+
+    if config.verify_email {
+        HttpAppErr::new(StatusCode::FORBIDDEN, "email is not verified")
+    } else {
+        HttpAppErr::new(StatusCode::FORBIDDEN, "account is not fully set up")
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
 struct LoginRequest {
     pub login: String,
     pub password: String,
 }
 
+impl Validate for LoginRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator
+            .required("login", &self.login)
+            .length("login", &self.login, 1, 254)
+            .required("password", &self.password)
+            .length("password", &self.password, 1, 128);
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct LoginResponse {
     pub access_token: String,
@@ -65,3 +174,183 @@ impl IntoResponse for LoginResponse {
         (StatusCode::OK, Json(self)).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use axum::{Router, body::Body, extract::connect_info::MockConnectInfo, http::Request};
+    use http::{Method, StatusCode, header::RETRY_AFTER};
+    use tower::ServiceExt;
+
+    use crate::{
+        config::AuthConfig,
+        keycloak::{
+            mock::server::{MockKeycloak, TEST_REALM, test_realm_description},
+            services::{
+                management::KeycloakManagement, requests::create_user::CreateUserRequest,
+                seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+            },
+        },
+        rate_limit::login_limiter_implementation::InMemoryLoginLimiter,
+    };
+
+    use super::{LoginResponse, create_login_router};
+
+    async fn start_keycloak_with_user() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        manager
+            .create_user(&CreateUserRequest::new(
+                &TEST_REALM,
+                &"jane@example.com",
+                &"secret",
+            ))
+            .await
+            .unwrap();
+
+        keycloak
+    }
+
+    fn login_router(keycloak: &MockKeycloak) -> Router {
+        login_router_with(keycloak, keycloak.create_config())
+    }
+
+    fn login_router_with(keycloak: &MockKeycloak, config: Arc<AuthConfig>) -> Router {
+        let limiter = Arc::new(InMemoryLoginLimiter::new(config.login_limits.clone()));
+
+        create_login_router(keycloak.create_client(), config, limiter)
+            .layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 50000))))
+    }
+
+    fn login_request(password: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/login")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"login":"jane@example.com","password":"{password}"}}"#
+            )))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn login_returns_tokens() {
+        let keycloak = start_keycloak_with_user().await;
+        let response = login_router(&keycloak)
+            .oneshot(login_request("secret"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let tokens: LoginResponse = serde_json::from_slice(&body).unwrap();
+        assert!(!tokens.access_token.is_empty());
+        assert!(!tokens.refresh_token.is_empty());
+    }
+
+    #[tokio::test]
+    async fn login_rejects_wrong_password() {
+        let keycloak = start_keycloak_with_user().await;
+        let response = login_router(&keycloak)
+            .oneshot(login_request("wrong"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn login_refuses_unverified_email() {
+        let keycloak = start_keycloak_with_user().await;
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            let user = realm.users.iter_mut().next().unwrap();
+            user.required_actions = vec!["VERIFY_EMAIL".to_owned()];
+        });
+
+        let mut config = keycloak.create_config();
+        Arc::get_mut(&mut config).unwrap().keycloak.verify_email = true;
+
+        let response = login_router_with(&keycloak, config)
+            .oneshot(login_request("secret"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("email is not verified"));
+    }
+
+    #[tokio::test]
+    async fn login_locks_out_after_repeated_failures() {
+        let keycloak = start_keycloak_with_user().await;
+        let router = login_router(&keycloak);
+        let lockout = keycloak
+            .create_config()
+            .login_limits
+            .lockout
+            .clone()
+            .unwrap();
+
+        for _ in 0..lockout.failures {
+            let response = router
+                .clone()
+                .oneshot(login_request("wrong"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let token_requests = keycloak.request_count(Method::POST, "/openid-connect/token");
+        let response = router.oneshot(login_request("secret")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        assert_eq!(
+            keycloak.request_count(Method::POST, "/openid-connect/token"),
+            token_requests
+        );
+    }
+
+    #[tokio::test]
+    async fn login_authenticates_confidential_client_with_secret() {
+        let keycloak = start_keycloak_with_user().await;
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            let client = &mut realm.clients[0];
+            client.public_client = false;
+            client.secret = "app_secret".to_owned();
+        });
+
+        let response = login_router(&keycloak)
+            .oneshot(login_request("secret"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut config = keycloak.create_config();
+        let keycloak_config = &mut Arc::get_mut(&mut config).unwrap().keycloak;
+        keycloak_config.public_client = false;
+        keycloak_config.client_secret = Some("app_secret".to_owned());
+
+        let response = login_router_with(&keycloak, config)
+            .oneshot(login_request("secret"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
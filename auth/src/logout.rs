@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use axum::{Router, extract::State, response, routing::post};
+use futures::TryFutureExt;
+use http::{HeaderMap, StatusCode};
+use reqwest::Client;
+use serde::Deserialize;
+use utils::{
+    errors::HttpAppErr,
+    http::ResponseExtended,
+    validation::{ValidJson, Validate, Validator},
+};
+
+use crate::{
+    config::AuthConfig,
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        keycloak_factory::create_default_routes,
+        services::{
+            responses::oauth_error::{INVALID_GRANT, OAuthErrorResponse},
+            routes::Routes,
+        },
+    },
+    login::TokenState,
+    tenant::resolve_tenant,
+};
+
+pub fn create_logout_router(client: Arc<Client>, config: Arc<AuthConfig>) -> Router {
+    Router::new()
+        .route("/api/logout", post(logout))
+        .with_state(TokenState { client, config })
+}
+
+async fn logout(
+    State(state): State<TokenState>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<LogoutRequest>,
+) -> response::Result<StatusCode> {
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let routes = create_default_routes(&state.config.keycloak);
+
+    let logout_url = routes.get_logout_route(&tenant.realm).log_err().await?;
+
+    let mut params = state.config.keycloak.client_params();
+    params.insert("refresh_token", request.refresh_token);
+
+    let response = state
+        .client
+        .post(logout_url)
+        .form(&params)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .inspect_err(|err| log::error!("logout err: {err}"))
+        .map_err(|_| HttpAppErr::new(StatusCode::FAILED_DEPENDENCY, "keycloak failed"))
+        .await?;
+
+    if response.status() == StatusCode::BAD_REQUEST {
+        let body = response.text().await.unwrap_or_default();
+
+        return Err(reject_invalid_grant(&body).into());
+    }
+
+    response
+        .ensure_success()
+        .await_err_as_failed_dependency()
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn reject_invalid_grant(body: &str) -> HttpAppErr {
+    let invalid_grant = serde_json::from_str::<OAuthErrorResponse>(body)
+        .is_ok_and(|error| error.error == INVALID_GRANT);
+
+    if invalid_grant {
+        HttpAppErr::new(
+            StatusCode::UNAUTHORIZED,
+            "refresh token is invalid or expired",
+        )
+    } else {
+        log::warn!("logout rejected by keycloak: {body}");
+        HttpAppErr::new(StatusCode::FAILED_DEPENDENCY, "keycloak failed")
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LogoutRequest {
+    pub refresh_token: String,
+}
+
+impl Validate for LogoutRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.required("refresh_token", &self.refresh_token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, body::Body, http::Request};
+    use http::{Method, StatusCode};
+    use tower::ServiceExt;
+
+    use crate::keycloak::{
+        mock::server::{MockKeycloak, TEST_REALM, test_realm_description},
+        services::{
+            management::KeycloakManagement, requests::create_user::CreateUserRequest,
+            seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+        },
+    };
+
+    use super::create_logout_router;
+
+    async fn start_keycloak_with_user() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        manager
+            .create_user(&CreateUserRequest::new(
+                &TEST_REALM,
+                &"jane@example.com",
+                &"Secret123",
+            ))
+            .await
+            .unwrap();
+
+        keycloak
+    }
+
+    fn logout_router(keycloak: &MockKeycloak) -> Router {
+        create_logout_router(keycloak.create_client(), keycloak.create_config())
+    }
+
+    fn logout_request(refresh_token: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/logout")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"refresh_token":"{refresh_token}"}}"#
+            )))
+            .unwrap()
+    }
+
+    fn session_count(keycloak: &MockKeycloak) -> usize {
+        keycloak.with_data(|data| data.realm_mut(TEST_REALM).ok().unwrap().sessions.len())
+    }
+
+    fn sign_in(keycloak: &MockKeycloak) -> String {
+        keycloak.sign_in("jane@example.com");
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            realm.sessions.keys().next().unwrap().clone()
+        })
+    }
+
+    #[tokio::test]
+    async fn logout_ends_the_session() {
+        let keycloak = start_keycloak_with_user().await;
+        let refresh_token = sign_in(&keycloak);
+
+        let response = logout_router(&keycloak)
+            .oneshot(logout_request(&refresh_token))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(session_count(&keycloak), 0);
+    }
+
+    #[tokio::test]
+    async fn logout_rejects_unknown_refresh_token() {
+        let keycloak = start_keycloak_with_user().await;
+        sign_in(&keycloak);
+
+        let response = logout_router(&keycloak)
+            .oneshot(logout_request("not-a-session"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(session_count(&keycloak), 1);
+    }
+
+    #[tokio::test]
+    async fn logout_reports_keycloak_failure() {
+        let keycloak = start_keycloak_with_user().await;
+        let refresh_token = sign_in(&keycloak);
+        keycloak.fail(Method::POST, "/logout", StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = logout_router(&keycloak)
+            .oneshot(logout_request(&refresh_token))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FAILED_DEPENDENCY);
+        assert_eq!(session_count(&keycloak), 1);
+    }
+}
@@ -1,20 +1,36 @@
-pub mod create_customer;
-pub mod create_vendor;
+pub mod account;
+pub mod config;
+pub mod email_verification;
 pub mod kafka;
 pub mod keycloak;
 pub mod login;
+pub mod logout;
+pub mod me;
+pub mod password;
+pub mod rate_limit;
 pub mod refresh_token;
+pub mod register_account;
+pub mod registration;
+pub mod sessions;
+pub mod tenant;
 
 extern crate axum;
-use std::time::Duration;
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
-use axum::{Router, response::Result};
-use create_customer::create_customer_router;
-use create_vendor::create_vendor_router;
+use account::create_account_router;
+use axum::{Router, middleware, response::Result};
+use config::{AuthConfig, TenantConfig};
+use email_verification::create_email_verification_router;
 use futures::TryFutureExt;
+use kafka::{
+    kafka_producer::create_producer, outbox_implementation::SqliteEventOutbox,
+    outbox_relay::run_outbox_relay,
+};
 use keycloak::{
     keycloak_factory::create_default_manager_and_auth,
     services::{
+        authorization::AdminAccessTokenProvider,
+        realm_description::{RealmDescription, RoleDescription, SmtpServerDescription},
         seeding::{KeycloakSeeding, KeycloakSeedingArguments},
         seeding_implementation::DefaultKeycloakSeeding,
         watcher::KeycloakWatcher,
@@ -22,56 +38,233 @@ use keycloak::{
     },
 };
 use login::create_login_router;
+use logout::create_logout_router;
+use me::create_me_router;
+use password::create_password_router;
+use rate_limit::login_limiter_implementation::InMemoryLoginLimiter;
 use refresh_token::create_refresh_token_router;
+use register_account::create_registration_router;
+use reqwest::Client;
+use sessions::create_sessions_router;
+use tenant::{TenantValidators, select_tenant_validator};
 use tokio_util::sync::CancellationToken;
-use utils::{dotenv::configure_dotenv, env::env_var, errors::AppErr, logging::configure_logs};
+use utils::{
+    config::ConfigSource,
+    dotenv::configure_dotenv,
+    errors::AppErr,
+    health::{HealthChecks, create_health_router},
+    http::{HttpClientSettings, create_http_client},
+    jwt::{JwtValidator, JwtValidatorSettings},
+    kafka_consumer::ping_kafka,
+    logging::configure_logs,
+    readiness::wait_until_ready,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), AppErr> {
     configure_dotenv();
     _ = configure_logs(log::LevelFilter::Info)?;
 
-    let (keycloak_manager, auth_provider) = create_default_manager_and_auth();
+    let config_source = ConfigSource::load()?;
+    let config = Arc::new(config_source.read::<AuthConfig>()?);
+
+    let http_client = Arc::new(create_http_client(&HttpClientSettings::new(
+        "web3store-auth",
+    ))?);
+
+    let (keycloak_manager, auth_provider) =
+        create_default_manager_and_auth(&config.keycloak, http_client.clone());
 
-    let keycloak_watcher = &DefaultKeycloakWatcher::new(auth_provider);
-    let watcher_cancellation = &CancellationToken::new();
-    let watcher_cancellation_clone = watcher_cancellation.clone();
+    let startup_cancellation = CancellationToken::new();
+    let startup_cancellation_clone = startup_cancellation.clone();
 
     tokio::task::spawn(async move {
-        tokio::time::sleep(Duration::from_secs(60)).await;
-        watcher_cancellation_clone.clone().cancel();
+        _ = tokio::signal::ctrl_c().await;
+        startup_cancellation_clone.cancel();
     });
-    keycloak_watcher
-        .watch(&watcher_cancellation.clone())
-        .await?;
 
-    let keycloak_seeder = &DefaultKeycloakSeeding::new(keycloak_manager);
+    let keycloak_watcher =
+        &DefaultKeycloakWatcher::new(auth_provider.clone(), config.startup.clone());
+    keycloak_watcher.watch(&startup_cancellation).await?;
 
-    keycloak_seeder
-        .seed(KeycloakSeedingArguments::new(
-            &env_var("KEYCLOAK_REALM")?,
-            &env_var("KEYCLOAK_CLIENT")?,
-            &env_var("KEYCLOAK_CLIENT_SECRET")?,
-            &env_var("KEYCLOAK_CUSTOMER_ROLE")?,
-            &env_var("KEYCLOAK_VENDOR_ROLE")?,
-        ))
-        .await?;
+    wait_until_ready("kafka", &config.startup, &startup_cancellation, |_| {
+        ping_kafka(&config.kafka.host, config.startup.attempt_timeout)
+    })
+    .await?;
+
+    let keycloak_seeder = &DefaultKeycloakSeeding::new(keycloak_manager.clone());
+
+    for tenant in config.tenants.iter() {
+        log::info!("seeding tenant {0}", tenant.id);
+        keycloak_seeder
+            .seed(create_realm_description(&config, tenant, &config_source)?)
+            .await?;
+    }
+
+    let outbox = Arc::new(SqliteEventOutbox::open(&config.outbox_path)?);
+    let producer = create_producer(&config.kafka.host)?;
+    let login_limiter = Arc::new(InMemoryLoginLimiter::new(config.login_limits.clone()));
+    let resend_limiter = Arc::new(InMemoryLoginLimiter::new(config.resend_limits.clone()));
+    let reset_limiter = Arc::new(InMemoryLoginLimiter::new(config.reset_limits.clone()));
 
     let app = Router::new()
-        .merge(create_customer_router())
-        .merge(create_vendor_router())
-        .merge(create_login_router())
-        .merge(create_refresh_token_router());
+        .merge(create_registration_router(
+            keycloak_manager.clone(),
+            outbox.clone(),
+            config.clone(),
+        ))
+        .merge(create_login_router(
+            http_client.clone(),
+            config.clone(),
+            login_limiter.clone(),
+        ))
+        .merge(create_logout_router(http_client.clone(), config.clone()))
+        .merge(create_refresh_token_router(
+            http_client.clone(),
+            config.clone(),
+        ))
+        .merge(create_password_router(
+            keycloak_manager.clone(),
+            http_client.clone(),
+            config.clone(),
+            login_limiter.clone(),
+            reset_limiter.clone(),
+        ))
+        .merge(create_email_verification_router(
+            keycloak_manager.clone(),
+            config.clone(),
+            resend_limiter.clone(),
+        ))
+        .merge(create_me_router(keycloak_manager.clone(), config.clone()))
+        .merge(create_account_router(
+            keycloak_manager.clone(),
+            outbox.clone(),
+            config.clone(),
+        ))
+        .merge(create_sessions_router(
+            keycloak_manager.clone(),
+            config.clone(),
+        ))
+        .merge(create_health_router(create_health_checks(
+            auth_provider.clone(),
+            config.clone(),
+        )))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(TenantValidators::new(
+                config.clone(),
+                create_tenant_validators(&config, http_client.clone()),
+            )),
+            select_tenant_validator,
+        ));
 
-    let listener = tokio::net::TcpListener::bind(env_var("SERVICE_HOST")?)
+    let listener = tokio::net::TcpListener::bind(&config.service_host)
         .map_err(|err| AppErr::from_owned(format!("failed to bind: {err}")))
         .await?;
 
-    log::info!("app started at: {0}", env_var("SERVICE_HOST")?);
+    log::info!("app started at: {0}", config.service_host);
 
-    axum::serve(listener, app)
-        .await
-        .map_err(|err| AppErr::from_owned(format!("server failed {err}")))?;
+    tokio::try_join!(
+        run_outbox_relay(outbox.clone(), producer, startup_cancellation.clone()),
+        login_limiter.run_sweeper(startup_cancellation.clone()),
+        resend_limiter.run_sweeper(startup_cancellation.clone()),
+        reset_limiter.run_sweeper(startup_cancellation.clone()),
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>()
+        )
+        .with_graceful_shutdown(startup_cancellation.clone().cancelled_owned())
+        .into_future()
+        .map_err(|err| AppErr::from_owned(format!("server failed {err}")))
+    )?;
 
     Ok(())
 }
+
+fn create_realm_description(
+    config: &AuthConfig,
+    tenant: &TenantConfig,
+    config_source: &ConfigSource,
+) -> Result<RealmDescription, AppErr> {
+    let mut description = match &tenant.realm_file {
+        Some(path) => RealmDescription::load(path, config_source)?,
+        None => {
+            let mut args = KeycloakSeedingArguments::new(
+                &tenant.realm,
+                &config.keycloak.client,
+                &config.keycloak.client_secret.clone().unwrap_or_default(),
+                &config.keycloak.customer_role,
+                "store customer",
+                &config.keycloak.vendor_role,
+                "store vendor",
+            );
+            args.public_client = config.keycloak.public_client;
+
+            RealmDescription::from(args)
+        }
+    };
+
+    description.merge_client_roles(
+        &config.keycloak.client,
+        config
+            .accounts
+            .iter()
+            .map(|account| RoleDescription {
+                name: account.role.clone(),
+                description: format!("store {0}", account.name),
+                composites: vec![],
+            })
+            .collect(),
+    )?;
+
+    description.realm = tenant.realm.clone();
+
+    if config.keycloak.verify_email {
+        description.verify_email = true;
+    }
+    if let Some(smtp) = &config.keycloak.smtp {
+        description.smtp_server = Some(SmtpServerDescription {
+            host: smtp.host.clone(),
+            port: smtp.port,
+            from: smtp.from.clone(),
+        });
+    }
+
+    Ok(description)
+}
+
+fn create_tenant_validators(
+    config: &AuthConfig,
+    http_client: Arc<Client>,
+) -> HashMap<String, Arc<JwtValidator>> {
+    config
+        .tenants
+        .iter()
+        .map(|tenant| {
+            let validator = JwtValidator::new(
+                JwtValidatorSettings {
+                    host: config.keycloak.host.clone(),
+                    realms: vec![tenant.realm.clone()],
+                    client: config.keycloak.client.clone(),
+                },
+                http_client.clone(),
+            );
+
+            (tenant.id.clone(), Arc::new(validator))
+        })
+        .collect()
+}
+
+fn create_health_checks(
+    auth_provider: Arc<impl AdminAccessTokenProvider + Send + Sync + 'static>,
+    config: Arc<AuthConfig>,
+) -> HealthChecks {
+    HealthChecks::new()
+        .with_check("keycloak", move || {
+            let auth_provider = auth_provider.clone();
+            async move { auth_provider.get_access_token().await.map(|_| ()) }
+        })
+        .with_check("kafka", move || {
+            let config = config.clone();
+            async move { ping_kafka(&config.kafka.host, config.startup.attempt_timeout).await }
+        })
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::State,
+    response::{IntoResponse, Result},
+    routing::get,
+};
+use http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use utils::{errors::HttpAppErr, jwt::AuthUser};
+
+use crate::{
+    config::AuthConfig,
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        services::{
+            management::KeycloakManagement,
+            queries::{
+                clients::ClientsQuery, user::UserQuery, user_client_roles::UserClientRolesQuery,
+            },
+        },
+    },
+    tenant::resolve_tenant,
+};
+
+pub struct MeState<TManager> {
+    pub manager: Arc<TManager>,
+    pub config: Arc<AuthConfig>,
+}
+
+impl<TManager> Clone for MeState<TManager> {
+    fn clone(&self) -> Self {
+        MeState {
+            manager: self.manager.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+pub fn create_me_router<TManager>(manager: Arc<TManager>, config: Arc<AuthConfig>) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/api/me", get(me::<TManager>))
+        .with_state(MeState { manager, config })
+}
+
+async fn me<TManager>(
+    State(state): State<MeState<TManager>>,
+    headers: HeaderMap,
+    user: AuthUser,
+) -> Result<MeResponse>
+where
+    TManager: KeycloakManagement + Send + Sync,
+{
+    let manager = state.manager;
+
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+    let client_name = &state.config.keycloak.client;
+
+    let profile = manager
+        .get_user_by_id(&UserQuery::new(realm_name, &user.id))
+        .await_err_as_failed_dependency()
+        .await?
+        .ok_or(HttpAppErr::new(StatusCode::NOT_FOUND, "user not found"))?;
+
+    let clients = manager
+        .query_clients(&ClientsQuery::new(realm_name, client_name))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    let client = clients
+        .into_iter()
+        .find(|client| &client.client_id == client_name)
+        .ok_or(HttpAppErr::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "client not found",
+        ))?;
+
+    let roles = manager
+        .query_user_client_roles(&UserClientRolesQuery::new(realm_name, &user.id, &client.id))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    Ok(MeResponse {
+        id: profile.id,
+        email: profile.email.or(user.email),
+        email_verified: profile.email_verified,
+        first_name: profile.first_name,
+        last_name: profile.last_name,
+        roles: roles.into_iter().map(|role| role.name).collect(),
+    })
+}
+
+#[derive(Serialize)]
+struct MeResponse {
+    pub id: String,
+    pub email: Option<String>,
+    #[serde(rename = "emailVerified")]
+    pub email_verified: bool,
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+    pub roles: Vec<String>,
+}
+
+impl IntoResponse for MeResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Extension, Router, body::Body, http::Request};
+    use http::{Method, StatusCode, header::AUTHORIZATION};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::keycloak::{
+        mock::server::{
+            MockKeycloak, TEST_CLIENT, TEST_CUSTOMER_ROLE, TEST_REALM, test_realm_description,
+        },
+        services::{
+            management::KeycloakManagement, requests::create_user::CreateUserRequest,
+            seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+        },
+    };
+
+    use super::create_me_router;
+
+    async fn start_keycloak_with_customer() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        manager
+            .create_user(&CreateUserRequest::new(
+                &TEST_REALM,
+                &"jane@example.com",
+                &"Secret123",
+            ))
+            .await
+            .unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            let role = realm
+                .client(TEST_CLIENT)
+                .unwrap()
+                .roles
+                .iter()
+                .find(|role| role.name == TEST_CUSTOMER_ROLE)
+                .unwrap()
+                .id
+                .clone();
+
+            let user = realm.users.iter_mut().next().unwrap();
+            user.email = Some("jane@example.com".to_owned());
+            user.email_verified = true;
+            user.first_name = Some("Jane".to_owned());
+            user.last_name = Some("Doe".to_owned());
+            user.roles.push(role);
+        });
+
+        keycloak
+    }
+
+    fn me_router(keycloak: &MockKeycloak) -> Router {
+        create_me_router(keycloak.create_manager(), keycloak.create_config())
+            .layer(Extension(keycloak.create_validator()))
+    }
+
+    fn me_request(token: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder().method(Method::GET).uri("/api/me");
+
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn returns_profile_and_client_roles_of_current_user() {
+        let keycloak = start_keycloak_with_customer().await;
+        let token = keycloak.sign_in("jane@example.com");
+
+        let response = me_router(&keycloak)
+            .oneshot(me_request(Some(&token)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let me: Value = serde_json::from_slice(&body).unwrap();
+
+        let user_id = keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            realm.user("jane@example.com").unwrap().id.clone()
+        });
+
+        assert_eq!(me["id"], user_id.as_str());
+        assert_eq!(me["email"], "jane@example.com");
+        assert_eq!(me["emailVerified"], true);
+        assert_eq!(me["firstName"], "Jane");
+        assert_eq!(me["lastName"], "Doe");
+        assert!(
+            me["roles"]
+                .as_array()
+                .unwrap()
+                .contains(&Value::from(TEST_CUSTOMER_ROLE))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_request_without_token() {
+        let keycloak = start_keycloak_with_customer().await;
+
+        let response = me_router(&keycloak)
+            .oneshot(me_request(None))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,593 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    Router,
+    extract::{ConnectInfo, State},
+    response::Result,
+    routing::post,
+};
+use futures::TryFutureExt;
+use http::{HeaderMap, StatusCode};
+use reqwest::Client;
+use serde::Deserialize;
+use utils::{
+    errors::{AppErr, HttpAppErr},
+    http::ResponseExtended,
+    jwt::AuthUser,
+    validation::{ValidJson, Validate, Validator},
+};
+
+use crate::{
+    config::{AuthConfig, KeycloakConfig},
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        keycloak_factory::create_default_routes,
+        services::{
+            management::KeycloakManagement,
+            queries::users::UsersQuery,
+            requests::{
+                execute_actions_email::ExecuteActionsEmailRequest,
+                reset_password::ResetPasswordRequest,
+            },
+            routes::Routes,
+        },
+    },
+    rate_limit::{
+        client_ip::client_ip,
+        login_limiter::{LoginAttempt, LoginLimiter, enforce_limit},
+    },
+    tenant::resolve_tenant,
+};
+
+pub struct PasswordState<TManager, TLimiter> {
+    pub manager: Arc<TManager>,
+    pub client: Arc<Client>,
+    pub config: Arc<AuthConfig>,
+    pub login_limiter: Arc<TLimiter>,
+    pub reset_limiter: Arc<TLimiter>,
+}
+
+impl<TManager, TLimiter> Clone for PasswordState<TManager, TLimiter> {
+    fn clone(&self) -> Self {
+        PasswordState {
+            manager: self.manager.clone(),
+            client: self.client.clone(),
+            config: self.config.clone(),
+            login_limiter: self.login_limiter.clone(),
+            reset_limiter: self.reset_limiter.clone(),
+        }
+    }
+}
+
+pub fn create_password_router<TManager, TLimiter>(
+    manager: Arc<TManager>,
+    client: Arc<Client>,
+    config: Arc<AuthConfig>,
+    login_limiter: Arc<TLimiter>,
+    reset_limiter: Arc<TLimiter>,
+) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+    TLimiter: LoginLimiter + Send + Sync + 'static,
+{
+    Router::new()
+        .route(
+            "/api/password/forgot",
+            post(forgot_password::<TManager, TLimiter>),
+        )
+        .route(
+            "/api/password/change",
+            post(change_password::<TManager, TLimiter>),
+        )
+        .with_state(PasswordState {
+            manager,
+            client,
+            config,
+            login_limiter,
+            reset_limiter,
+        })
+}
+
+async fn forgot_password<TManager, TLimiter>(
+    State(state): State<PasswordState<TManager, TLimiter>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<ForgotPasswordRequest>,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+    TLimiter: LoginLimiter + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    // counted before the lookup, so requests for unknown addresses use up the allowance as well
+    let attempt = LoginAttempt::new(
+        &client_ip(&headers, &peer, &state.config.trusted_proxies),
+        &format!("{0}:{1}", tenant.id, request.email),
+    );
+    enforce_limit(
+        state.reset_limiter.as_ref(),
+        &attempt,
+        "password reset emails",
+    )
+    .await?;
+
+    let users = state
+        .manager
+        .query_users(&UsersQuery::new(realm_name, &request.email))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    let user = users
+        .into_iter()
+        .find(|user| user.username.eq_ignore_ascii_case(&request.email));
+
+    match user {
+        Some(user) if user.enabled => {
+            state
+                .manager
+                .execute_actions_email(&ExecuteActionsEmailRequest::new_update_password(
+                    realm_name, &user.id,
+                ))
+                .await_err_as_failed_dependency()
+                .await?;
+
+            log::info!("password reset email sent to user {0}", user.id);
+        }
+        _ => log::info!("password reset requested for unknown user"),
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn change_password<TManager, TLimiter>(
+    State(state): State<PasswordState<TManager, TLimiter>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    user: AuthUser,
+    ValidJson(request): ValidJson<ChangePasswordRequest>,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+    TLimiter: LoginLimiter + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    let username = user.username.or(user.email).ok_or(HttpAppErr::new(
+        StatusCode::UNAUTHORIZED,
+        "token has no username",
+    ))?;
+
+    // checking the current password is a password grant, so it shares the login lockout
+    let attempt = LoginAttempt::new(
+        &client_ip(&headers, &peer, &state.config.trusted_proxies),
+        &format!("{0}:{1}", tenant.id, username),
+    );
+    enforce_limit(state.login_limiter.as_ref(), &attempt, "login attempts").await?;
+
+    let verified = verify_password(
+        &state.client,
+        &state.config.keycloak,
+        realm_name,
+        &username,
+        &request.current_password,
+    )
+    .await?;
+
+    if !verified {
+        state
+            .login_limiter
+            .record_failure(&attempt)
+            .await
+            .map_err(|err| HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+        return Err(
+            HttpAppErr::new(StatusCode::UNAUTHORIZED, "current password is incorrect").into(),
+        );
+    }
+
+    state
+        .login_limiter
+        .record_success(&attempt)
+        .await
+        .map_err(|err| HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+    state
+        .manager
+        .reset_password(&ResetPasswordRequest::new(
+            realm_name,
+            &user.id,
+            &request.new_password,
+        ))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    log::info!("password changed for user {0}", user.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn verify_password(
+    client: &Client,
+    config: &KeycloakConfig,
+    realm: &str,
+    username: &str,
+    password: &str,
+) -> Result<bool> {
+    let routes = create_default_routes(config);
+
+    let auth_url = routes
+        .get_auth_route(&realm)
+        .await_err_as_failed_dependency()
+        .await?;
+
+    let mut params = config.client_params();
+    params.insert("username", username.to_owned());
+    params.insert("password", password.to_owned());
+    params.insert("grant_type", "password".to_owned());
+
+    let response = client
+        .post(auth_url)
+        .form(&params)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .inspect_err(|err| log::error!("auth err: {err}"))
+        .map_err(|_| HttpAppErr::new(StatusCode::FAILED_DEPENDENCY, "keycloak failed"))
+        .await?;
+
+    if response.status() == StatusCode::UNAUTHORIZED {
+        return Ok(false);
+    }
+
+    let session = response
+        .ensure_success_json::<VerificationSession>()
+        .map_err(|err| HttpAppErr::failed_dependency(err.into()))
+        .await?;
+
+    // the password grant opened a session only to check the password, so close it right away
+    if let Err(err) = end_session(client, config, realm, &session.refresh_token).await {
+        log::warn!("failed to end password verification session: {err}");
+    }
+
+    Ok(true)
+}
+
+async fn end_session(
+    client: &Client,
+    config: &KeycloakConfig,
+    realm: &str,
+    refresh_token: &str,
+) -> Result<(), AppErr> {
+    let routes = create_default_routes(config);
+    let logout_url = routes.get_logout_route(&realm).await?;
+
+    let mut params = config.client_params();
+    params.insert("refresh_token", refresh_token.to_owned());
+
+    client
+        .post(logout_url)
+        .form(&params)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .send()
+        .map_err(|err| AppErr::from_owned(format!("logout request failed: {err}")))
+        .await?
+        .ensure_success()
+        .await
+}
+
+#[derive(Deserialize)]
+struct VerificationSession {
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+impl Validate for ForgotPasswordRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator
+            .required("email", &self.email)
+            .email("email", &self.email);
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+impl Validate for ChangePasswordRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator
+            .required("current_password", &self.current_password)
+            .required("new_password", &self.new_password)
+            .password("new_password", &self.new_password);
+
+        if !self.new_password.is_empty() && self.new_password == self.current_password {
+            validator.error("new_password", "must differ from the current password");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use axum::{
+        Extension, Router, body::Body, extract::connect_info::MockConnectInfo, http::Request,
+    };
+    use http::{
+        Method, StatusCode,
+        header::{AUTHORIZATION, RETRY_AFTER},
+    };
+    use tower::ServiceExt;
+
+    use crate::{
+        keycloak::{
+            mock::server::{MockKeycloak, TEST_REALM, test_realm_description},
+            services::{
+                management::KeycloakManagement, requests::create_user::CreateUserRequest,
+                seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+            },
+        },
+        rate_limit::login_limiter_implementation::InMemoryLoginLimiter,
+    };
+
+    use super::create_password_router;
+
+    async fn start_keycloak_with_user() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        manager
+            .create_user(&CreateUserRequest::new(
+                &TEST_REALM,
+                &"jane@example.com",
+                &"Secret123",
+            ))
+            .await
+            .unwrap();
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            realm.users.iter_mut().next().unwrap().email = Some("jane@example.com".to_owned());
+        });
+
+        keycloak
+    }
+
+    fn password_router(keycloak: &MockKeycloak) -> Router {
+        let config = keycloak.create_config();
+        let login_limiter = Arc::new(InMemoryLoginLimiter::new(config.login_limits.clone()));
+        let reset_limiter = Arc::new(InMemoryLoginLimiter::new(config.reset_limits.clone()));
+
+        create_password_router(
+            keycloak.create_manager(),
+            keycloak.create_client(),
+            config,
+            login_limiter,
+            reset_limiter,
+        )
+        .layer(Extension(keycloak.create_validator()))
+        .layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 50000))))
+    }
+
+    fn forgot_request(email: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/password/forgot")
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(r#"{{"email":"{email}"}}"#)))
+            .unwrap()
+    }
+
+    fn sent_actions(keycloak: &MockKeycloak) -> Vec<String> {
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            realm.user("jane@example.com").unwrap().sent_actions.clone()
+        })
+    }
+
+    fn change_request(token: &str, current_password: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/password/change")
+            .header("Content-Type", "application/json")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::from(format!(
+                r#"{{"current_password":"{current_password}","new_password":"Better456"}}"#
+            )))
+            .unwrap()
+    }
+
+    fn password_and_sessions(keycloak: &MockKeycloak) -> (Option<String>, usize) {
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            let password = realm.user("jane@example.com").unwrap().password.clone();
+
+            (password, realm.sessions.len())
+        })
+    }
+
+    #[tokio::test]
+    async fn changes_password_without_leaving_verification_session() {
+        let keycloak = start_keycloak_with_user().await;
+        let token = keycloak.sign_in("jane@example.com");
+
+        let response = password_router(&keycloak)
+            .oneshot(change_request(&token, "Secret123"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            password_and_sessions(&keycloak),
+            (Some("Better456".to_owned()), 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_current_password() {
+        let keycloak = start_keycloak_with_user().await;
+        let token = keycloak.sign_in("jane@example.com");
+
+        let response = password_router(&keycloak)
+            .oneshot(change_request(&token, "Wrong123"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            password_and_sessions(&keycloak),
+            (Some("Secret123".to_owned()), 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_token() {
+        let keycloak = start_keycloak_with_user().await;
+
+        let response = password_router(&keycloak)
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/password/change")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        r#"{"current_password":"Secret123","new_password":"Better456"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            password_and_sessions(&keycloak).0.as_deref(),
+            Some("Secret123")
+        );
+    }
+
+    #[tokio::test]
+    async fn locks_password_change_after_repeated_wrong_passwords() {
+        let keycloak = start_keycloak_with_user().await;
+        let token = keycloak.sign_in("jane@example.com");
+        let router = password_router(&keycloak);
+        let lockout = keycloak
+            .create_config()
+            .login_limits
+            .lockout
+            .clone()
+            .unwrap();
+
+        for _ in 0..lockout.failures {
+            let response = router
+                .clone()
+                .oneshot(change_request(&token, "Wrong123"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let response = router
+            .oneshot(change_request(&token, "Secret123"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        assert_eq!(
+            password_and_sessions(&keycloak).0.as_deref(),
+            Some("Secret123")
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_reset_email_to_known_user() {
+        let keycloak = start_keycloak_with_user().await;
+
+        let response = password_router(&keycloak)
+            .oneshot(forgot_request("jane@example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(sent_actions(&keycloak), ["UPDATE_PASSWORD"]);
+    }
+
+    #[tokio::test]
+    async fn looks_up_reset_email_case_insensitively() {
+        let keycloak = start_keycloak_with_user().await;
+
+        let response = password_router(&keycloak)
+            .oneshot(forgot_request("Jane@Example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(sent_actions(&keycloak), ["UPDATE_PASSWORD"]);
+    }
+
+    #[tokio::test]
+    async fn accepts_unknown_or_disabled_user_without_sending() {
+        let keycloak = start_keycloak_with_user().await;
+        let router = password_router(&keycloak);
+
+        let response = router
+            .clone()
+            .oneshot(forgot_request("nobody@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        keycloak.with_data(|data| {
+            let realm = data.realm_mut(TEST_REALM).ok().unwrap();
+            realm.users.iter_mut().next().unwrap().enabled = false;
+        });
+
+        let response = router
+            .oneshot(forgot_request("jane@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        assert!(sent_actions(&keycloak).is_empty());
+    }
+
+    #[tokio::test]
+    async fn limits_reset_emails_per_email() {
+        let keycloak = start_keycloak_with_user().await;
+        let router = password_router(&keycloak);
+        let allowed = keycloak.create_config().reset_limits.login_attempts;
+
+        for _ in 0..allowed {
+            let response = router
+                .clone()
+                .oneshot(forgot_request("jane@example.com"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+
+        let response = router
+            .oneshot(forgot_request("JANE@example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        assert_eq!(sent_actions(&keycloak).len(), allowed as usize);
+    }
+}
@@ -0,0 +1,84 @@
+use std::net::{IpAddr, SocketAddr};
+
+use http::HeaderMap;
+
+// forwarding headers are only believed when the connection comes from a configured proxy,
+// anyone reaching the service directly could put any address in them
+pub fn client_ip(headers: &HeaderMap, peer: &SocketAddr, trusted_proxies: &[IpAddr]) -> String {
+    if !trusted_proxies.contains(&peer.ip()) {
+        return peer.ip().to_string();
+    }
+
+    let forwarded_for = headers
+        .get("X-Forwarded-For")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').map(str::trim).find(|ip| !ip.is_empty()));
+
+    let real_ip = || {
+        headers
+            .get("X-Real-IP")
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+
+    forwarded_for
+        .or_else(real_ip)
+        .map(str::to_owned)
+        .unwrap_or_else(|| peer.ip().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, SocketAddr};
+
+    use http::{HeaderMap, HeaderValue};
+
+    use super::client_ip;
+
+    const PROXY: &str = "172.28.0.10:41000";
+    const CLIENT: &str = "203.0.113.5:52000";
+
+    fn trusted() -> Vec<IpAddr> {
+        vec!["172.28.0.10".parse().unwrap()]
+    }
+
+    fn forwarded(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn uses_address_appended_by_trusted_proxy() {
+        let peer: SocketAddr = PROXY.parse().unwrap();
+
+        assert_eq!(
+            client_ip(&forwarded("1.1.1.1, 10.0.0.7"), &peer, &trusted()),
+            "10.0.0.7"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_real_ip_and_proxy_address() {
+        let peer: SocketAddr = PROXY.parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Real-IP", HeaderValue::from_static("10.0.0.8"));
+
+        assert_eq!(client_ip(&headers, &peer, &trusted()), "10.0.0.8");
+        assert_eq!(
+            client_ip(&HeaderMap::new(), &peer, &trusted()),
+            "172.28.0.10"
+        );
+    }
+
+    #[test]
+    fn ignores_forwarding_headers_from_direct_clients() {
+        let peer: SocketAddr = CLIENT.parse().unwrap();
+
+        assert_eq!(
+            client_ip(&forwarded("10.0.0.7"), &peer, &trusted()),
+            "203.0.113.5"
+        );
+        assert_eq!(client_ip(&forwarded("10.0.0.7"), &peer, &[]), "203.0.113.5");
+    }
+}
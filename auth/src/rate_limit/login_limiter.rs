@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use axum::response::{ErrorResponse, IntoResponse};
+use http::{StatusCode, header::RETRY_AFTER};
+use utils::errors::{AppErr, HttpAppErr};
+
+pub struct LoginAttempt {
+    pub ip: String,
+    pub login: String,
+}
+
+impl LoginAttempt {
+    pub fn new(ip: &str, login: &str) -> Self {
+        LoginAttempt {
+            ip: ip.to_owned(),
+            login: login.trim().to_lowercase(),
+        }
+    }
+}
+
+pub enum LimitDecision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+pub fn too_many_requests(retry_after: Duration, what: &str) -> ErrorResponse {
+    let retry_after_secs = retry_after.as_secs_f64().ceil() as u64;
+
+    (
+        [(RETRY_AFTER, retry_after_secs.to_string())],
+        HttpAppErr::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            &format!("too many {what}, retry in {retry_after_secs}s"),
+        ),
+    )
+        .into_response()
+        .into()
+}
+
+pub async fn enforce_limit<TLimiter>(
+    limiter: &TLimiter,
+    attempt: &LoginAttempt,
+    what: &str,
+) -> Result<(), ErrorResponse>
+where
+    TLimiter: LoginLimiter,
+{
+    let decision = limiter
+        .check(attempt)
+        .await
+        .map_err(|err| HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+
+    if let LimitDecision::Limited { retry_after } = decision {
+        log::warn!("{what} for {0} from {1} limited", attempt.login, attempt.ip);
+
+        return Err(too_many_requests(retry_after, what));
+    }
+
+    Ok(())
+}
+
+pub trait LoginLimiter {
+    fn check(
+        &self,
+        attempt: &LoginAttempt,
+    ) -> impl Future<Output = Result<LimitDecision, AppErr>> + Send;
+
+    fn record_failure(
+        &self,
+        attempt: &LoginAttempt,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+
+    fn record_success(
+        &self,
+        attempt: &LoginAttempt,
+    ) -> impl Future<Output = Result<(), AppErr>> + Send;
+}
@@ -0,0 +1,293 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use tokio::{select, time::sleep};
+use tokio_util::sync::CancellationToken;
+use utils::errors::AppErr;
+
+use crate::config::LoginLimitConfig;
+
+use super::login_limiter::{LimitDecision, LoginAttempt, LoginLimiter};
+
+// keeps a tiny configured window from turning the sweeper into a busy loop
+const MIN_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+struct LimitEntry {
+    window_started: Instant,
+    last_seen: Instant,
+    attempts: u32,
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+impl LimitEntry {
+    fn new(now: Instant) -> Self {
+        LimitEntry {
+            window_started: now,
+            last_seen: now,
+            attempts: 0,
+            failures: 0,
+            locked_until: None,
+        }
+    }
+
+    // failures are forgotten together with the entry once the login stays quiet long enough
+    fn is_stale(&self, now: Instant, retention: Duration) -> bool {
+        now.duration_since(self.last_seen) >= retention
+            && self.locked_until.is_none_or(|until| until <= now)
+    }
+}
+
+pub struct InMemoryLoginLimiter {
+    config: LoginLimitConfig,
+    entries: Mutex<HashMap<String, LimitEntry>>,
+}
+
+impl InMemoryLoginLimiter {
+    pub fn new(config: LoginLimitConfig) -> Self {
+        InMemoryLoginLimiter {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn run_sweeper(&self, cancellation_token: CancellationToken) -> Result<(), AppErr> {
+        loop {
+            select! {
+                _ = sleep(self.config.window.max(MIN_SWEEP_INTERVAL)) => self.sweep(Instant::now()),
+                _ = cancellation_token.cancelled() => return Ok(()),
+            }
+        }
+    }
+
+    fn sweep(&self, now: Instant) {
+        let lockout = self.config.lockout.as_ref().map(|lockout| lockout.duration);
+        let retention = self.config.window.max(lockout.unwrap_or_default());
+        let mut entries = self.lock();
+        let before = entries.len();
+
+        entries.retain(|_, entry| !entry.is_stale(now, retention));
+
+        if entries.len() < before {
+            log::debug!("{0} login limit entries expired", before - entries.len());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, LimitEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn count_attempt(
+        &self,
+        entries: &mut HashMap<String, LimitEntry>,
+        key: String,
+        max_attempts: u32,
+        now: Instant,
+    ) -> Option<Duration> {
+        let entry = entries.entry(key).or_insert_with(|| LimitEntry::new(now));
+        entry.last_seen = now;
+
+        if let Some(until) = entry.locked_until.filter(|until| *until > now) {
+            return Some(until - now);
+        }
+
+        if now.duration_since(entry.window_started) >= self.config.window {
+            entry.window_started = now;
+            entry.attempts = 0;
+        }
+
+        if entry.attempts >= max_attempts {
+            return Some(self.config.window - now.duration_since(entry.window_started));
+        }
+
+        entry.attempts += 1;
+        None
+    }
+}
+
+impl LoginLimiter for InMemoryLoginLimiter {
+    async fn check(&self, attempt: &LoginAttempt) -> Result<LimitDecision, AppErr> {
+        let now = Instant::now();
+        let mut entries = self.lock();
+
+        let limited = [
+            self.count_attempt(
+                &mut entries,
+                format!("ip:{0}", attempt.ip),
+                self.config.ip_attempts,
+                now,
+            ),
+            self.count_attempt(
+                &mut entries,
+                format!("login:{0}", attempt.login),
+                self.config.login_attempts,
+                now,
+            ),
+        ];
+
+        Ok(match limited.into_iter().flatten().max() {
+            Some(retry_after) => LimitDecision::Limited { retry_after },
+            None => LimitDecision::Allowed,
+        })
+    }
+
+    async fn record_failure(&self, attempt: &LoginAttempt) -> Result<(), AppErr> {
+        let now = Instant::now();
+        let mut entries = self.lock();
+
+        let entry = entries
+            .entry(format!("login:{0}", attempt.login))
+            .or_insert_with(|| LimitEntry::new(now));
+
+        entry.last_seen = now;
+
+        let Some(lockout) = self.config.lockout.as_ref() else {
+            return Ok(());
+        };
+
+        entry.failures += 1;
+
+        if entry.failures >= lockout.failures {
+            log::warn!(
+                "login {0} locked for {1:?} after {2} failed attempts",
+                attempt.login,
+                lockout.duration,
+                entry.failures
+            );
+
+            entry.failures = 0;
+            entry.locked_until = Some(now + lockout.duration);
+        }
+
+        Ok(())
+    }
+
+    async fn record_success(&self, attempt: &LoginAttempt) -> Result<(), AppErr> {
+        if let Some(entry) = self.lock().get_mut(&format!("login:{0}", attempt.login)) {
+            entry.failures = 0;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        config::{LockoutConfig, LoginLimitConfig},
+        rate_limit::login_limiter::{LimitDecision, LoginAttempt, LoginLimiter},
+    };
+
+    use super::InMemoryLoginLimiter;
+
+    fn limiter() -> InMemoryLoginLimiter {
+        InMemoryLoginLimiter::new(LoginLimitConfig {
+            lockout: Some(LockoutConfig {
+                failures: 2,
+                duration: Duration::from_secs(300),
+            }),
+            ..LoginLimitConfig::without_lockout(5, 3, Duration::from_secs(60))
+        })
+    }
+
+    async fn is_limited(limiter: &InMemoryLoginLimiter, attempt: &LoginAttempt) -> bool {
+        matches!(
+            limiter.check(attempt).await.unwrap(),
+            LimitDecision::Limited { .. }
+        )
+    }
+
+    #[tokio::test]
+    async fn limits_attempts_per_login() {
+        let limiter = limiter();
+
+        for ip in ["10.0.0.1", "10.0.0.2", "10.0.0.3"] {
+            assert!(!is_limited(&limiter, &LoginAttempt::new(ip, "Jane")).await);
+        }
+
+        assert!(is_limited(&limiter, &LoginAttempt::new("10.0.0.4", "jane")).await);
+        assert!(!is_limited(&limiter, &LoginAttempt::new("10.0.0.4", "john")).await);
+    }
+
+    #[tokio::test]
+    async fn limits_attempts_per_ip() {
+        let limiter = limiter();
+
+        for login in ["a", "b", "c", "d", "e"] {
+            assert!(!is_limited(&limiter, &LoginAttempt::new("10.0.0.1", login)).await);
+        }
+
+        assert!(is_limited(&limiter, &LoginAttempt::new("10.0.0.1", "f")).await);
+        assert!(!is_limited(&limiter, &LoginAttempt::new("10.0.0.2", "f")).await);
+    }
+
+    #[tokio::test]
+    async fn locks_login_after_repeated_failures() {
+        let limiter = limiter();
+        let attempt = LoginAttempt::new("10.0.0.1", "jane");
+
+        limiter.record_failure(&attempt).await.unwrap();
+        assert!(!is_limited(&limiter, &attempt).await);
+
+        limiter.record_failure(&attempt).await.unwrap();
+        match limiter.check(&attempt).await.unwrap() {
+            LimitDecision::Limited { retry_after } => {
+                assert!(retry_after > Duration::from_secs(60));
+            }
+            LimitDecision::Allowed => panic!("login should be locked"),
+        }
+    }
+
+    #[tokio::test]
+    async fn success_resets_failures() {
+        let limiter = limiter();
+        let attempt = LoginAttempt::new("10.0.0.1", "jane");
+
+        limiter.record_failure(&attempt).await.unwrap();
+        limiter.record_success(&attempt).await.unwrap();
+        limiter.record_failure(&attempt).await.unwrap();
+
+        assert!(!is_limited(&limiter, &attempt).await);
+    }
+
+    #[tokio::test]
+    async fn sweep_expires_idle_entries_with_failures() {
+        let limiter = limiter();
+        let attempt = LoginAttempt::new("10.0.0.1", "jane");
+
+        limiter.record_failure(&attempt).await.unwrap();
+        assert!(!is_limited(&limiter, &attempt).await);
+
+        limiter.sweep(Instant::now() + Duration::from_secs(299));
+        assert_eq!(limiter.lock().len(), 2);
+
+        limiter.sweep(Instant::now() + Duration::from_secs(300));
+        assert!(limiter.lock().is_empty());
+
+        limiter.record_failure(&attempt).await.unwrap();
+        assert!(!is_limited(&limiter, &attempt).await);
+    }
+
+    #[tokio::test]
+    async fn never_locks_without_lockout() {
+        let limiter = InMemoryLoginLimiter::new(LoginLimitConfig::without_lockout(
+            5,
+            3,
+            Duration::from_secs(60),
+        ));
+        let attempt = LoginAttempt::new("10.0.0.1", "jane");
+
+        for _ in 0..10 {
+            limiter.record_failure(&attempt).await.unwrap();
+        }
+
+        assert!(!is_limited(&limiter, &attempt).await);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod client_ip;
+pub mod login_limiter;
+pub mod login_limiter_implementation;
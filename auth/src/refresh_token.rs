@@ -1,39 +1,53 @@
-use std::collections::HashMap;
+use std::sync::Arc;
 
 use axum::{
     Json, Router,
+    extract::State,
     response::{self, IntoResponse},
     routing::post,
 };
 use futures::TryFutureExt;
-use http::StatusCode;
+use http::{HeaderMap, StatusCode};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use utils::{env::env_var, errors::HttpAppErr, http::ResponseExtended};
+use utils::{
+    errors::HttpAppErr,
+    http::ResponseExtended,
+    validation::{ValidJson, Validate, Validator},
+};
 
-use crate::keycloak::{
-    keycloak_ex::KeycloakExtensions, keycloak_factory::create_default_routes,
-    services::routes::Routes,
+use crate::{
+    config::AuthConfig,
+    keycloak::{
+        keycloak_ex::KeycloakExtensions, keycloak_factory::create_default_routes,
+        services::routes::Routes,
+    },
+    login::TokenState,
+    tenant::resolve_tenant,
 };
 
-pub fn create_refresh_token_router() -> Router {
-    Router::new().route("/api/token", post(refresh_token))
+pub fn create_refresh_token_router(client: Arc<Client>, config: Arc<AuthConfig>) -> Router {
+    Router::new()
+        .route("/api/token", post(refresh_token))
+        .with_state(TokenState { client, config })
 }
 
-async fn refresh_token(Json(request): Json<LoginRequest>) -> response::Result<LoginResponse> {
-    let routes = create_default_routes();
+async fn refresh_token(
+    State(state): State<TokenState>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<LoginRequest>,
+) -> response::Result<LoginResponse> {
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let routes = create_default_routes(&state.config.keycloak);
 
-    let auth_url = routes
-        .get_auth_route(&env_var("KEYCLOAK_REALM")?)
-        .log_err()
-        .await?;
+    let auth_url = routes.get_auth_route(&tenant.realm).log_err().await?;
 
-    let mut params = HashMap::new();
-    params.insert("client_id", env_var("KEYCLOAK_CLIENT")?);
+    let mut params = state.config.keycloak.client_params();
     params.insert("refresh_token", request.refresh_token);
     params.insert("grant_type", "refresh_token".to_owned());
 
-    let response = Client::new()
+    let response = state
+        .client
         .post(auth_url)
         .form(&params)
         .header("Content-Type", "application/x-www-form-urlencoded")
@@ -47,11 +61,18 @@ async fn refresh_token(Json(request): Json<LoginRequest>) -> response::Result<Lo
     Ok(res)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
+#[serde(default)]
 struct LoginRequest {
     pub refresh_token: String,
 }
 
+impl Validate for LoginRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator.required("refresh_token", &self.refresh_token);
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct LoginResponse {
     pub access_token: String,
@@ -0,0 +1,799 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{Router, extract::State, response::Result, routing::post};
+use http::{HeaderMap, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use utils::{
+    errors::HttpAppErr,
+    jwt::AuthUser,
+    validation::{ValidJson, Validate, Validator},
+};
+
+use crate::{
+    config::{AuthConfig, KeycloakConfig},
+    kafka::outbox::{EventOutbox, OutboxMessage, user_event_key},
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        services::{
+            management::KeycloakManagement,
+            queries::{
+                clients::ClientsQuery, role::RoleQuery, user::UserQuery,
+                user_client_roles::UserClientRolesQuery,
+            },
+            requests::assign_roles::{AssignRoleRequest, AssignRolesRequest},
+            responses::client::ClientResponse,
+        },
+    },
+    registration::{AccountType, NewAccount, RegistrationSaga, RegistrationState},
+    tenant::resolve_tenant,
+};
+
+const PROFILE_FIELD_MAX_LENGTH: usize = 255;
+
+pub fn create_registration_router<TManager, TOutbox>(
+    manager: Arc<TManager>,
+    outbox: Arc<TOutbox>,
+    config: Arc<AuthConfig>,
+) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+    TOutbox: EventOutbox + Send + Sync + 'static,
+{
+    config
+        .accounts
+        .iter()
+        .fold(Router::new(), |router, account| {
+            router.merge(create_account_type_router(
+                manager.clone(),
+                outbox.clone(),
+                config.clone(),
+                AccountType::new(account),
+            ))
+        })
+}
+
+pub fn create_account_type_router<TManager, TOutbox>(
+    manager: Arc<TManager>,
+    outbox: Arc<TOutbox>,
+    config: Arc<AuthConfig>,
+    account: AccountType,
+) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+    TOutbox: EventOutbox + Send + Sync + 'static,
+{
+    let mut router = Router::new().route(&account.path, post(register::<TManager, TOutbox>));
+
+    // upgrades are opt-in per account type, config rejects them for types with profile fields
+    if account.upgrade_from.is_some() {
+        router = router.route(
+            &format!("{0}/upgrade", account.path),
+            post(upgrade::<TManager, TOutbox>),
+        );
+    }
+
+    router.with_state(RegistrationState {
+        manager,
+        outbox,
+        config,
+        account: Arc::new(account),
+    })
+}
+
+async fn register<TManager, TOutbox>(
+    State(state): State<RegistrationState<TManager, TOutbox>>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<RegistrationRequest>,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+    TOutbox: EventOutbox + Send + Sync,
+{
+    let manager = state.manager;
+    let account = state.account;
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    let profile = read_profile(&account, request.profile)?;
+
+    let client = find_client(manager.as_ref(), realm_name, &state.config.keycloak).await?;
+
+    let role = manager
+        .query_role(&RoleQuery::new(realm_name, &client.id, &account.role))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    // keycloak stores emails lowercased, so every event about this user carries the same form
+    let new_account = NewAccount {
+        tenant: tenant.id.clone(),
+        email: request.email.to_lowercase(),
+        profile,
+    };
+    let event = OutboxMessage::new(
+        &account.topic,
+        &user_event_key(&tenant.id, &new_account.email),
+        &(account.event)(&new_account),
+    )?;
+
+    RegistrationSaga::new(manager.as_ref(), realm_name, &new_account.email)
+        .with_profile(&new_account.profile)
+        .with_email_verification(state.config.keycloak.verify_email)
+        .run(
+            &request.password,
+            &client,
+            &role,
+            state.outbox.as_ref(),
+            &event,
+        )
+        .await?;
+
+    log::info!("{0} registered, event recorded", account.name);
+
+    Ok(StatusCode::CREATED)
+}
+
+async fn upgrade<TManager, TOutbox>(
+    State(state): State<RegistrationState<TManager, TOutbox>>,
+    headers: HeaderMap,
+    user: AuthUser,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+    TOutbox: EventOutbox + Send + Sync,
+{
+    let manager = state.manager;
+    let account = state.account;
+
+    let upgrade_from = account.upgrade_from.as_deref().unwrap_or_default();
+    user.require_role(upgrade_from)?;
+
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    let profile = manager
+        .get_user_by_id(&UserQuery::new(realm_name, &user.id))
+        .await_err_as_failed_dependency()
+        .await?
+        .ok_or(HttpAppErr::new(StatusCode::NOT_FOUND, "user not found"))?;
+
+    let email = profile.email.ok_or(HttpAppErr::new(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "user has no email",
+    ))?;
+
+    let client = find_client(manager.as_ref(), realm_name, &state.config.keycloak).await?;
+
+    let roles = manager
+        .query_user_client_roles(&UserClientRolesQuery::new(realm_name, &user.id, &client.id))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    if roles.iter().any(|role| role.name == account.role) {
+        return Err(HttpAppErr::new(
+            StatusCode::CONFLICT,
+            &format!("user is already a {0}", account.name),
+        )
+        .into());
+    }
+
+    let role = manager
+        .query_role(&RoleQuery::new(realm_name, &client.id, &account.role))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    let event = OutboxMessage::new(
+        &account.topic,
+        &user_event_key(&tenant.id, &email),
+        &(account.event)(&NewAccount {
+            tenant: tenant.id.clone(),
+            email: email.clone(),
+            profile: BTreeMap::new(),
+        }),
+    )?;
+
+    let assignment = AssignRolesRequest::new(
+        realm_name,
+        &user.id,
+        &client.id,
+        &[AssignRoleRequest::new(&role.id, &role.name)],
+    );
+
+    manager
+        .assign_roles(&assignment)
+        .await_err_as_failed_dependency()
+        .await?;
+
+    if let Err(err) = state.outbox.enqueue(&event).await {
+        log::error!(
+            "failed to record {0} event for {email}, removing role: {err}",
+            account.name
+        );
+
+        if let Err(err) = manager.remove_roles(&assignment).await {
+            log::error!(
+                "failed to remove {0} role from {email} after upgrade failed: {err}",
+                account.name
+            );
+        }
+
+        return Err(HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err).into());
+    }
+
+    log::info!("user upgraded to {0}, event recorded", account.name);
+
+    Ok(StatusCode::OK)
+}
+
+async fn find_client<TManager>(
+    manager: &TManager,
+    realm: &str,
+    config: &KeycloakConfig,
+) -> Result<ClientResponse, HttpAppErr>
+where
+    TManager: KeycloakManagement + Send + Sync,
+{
+    manager
+        .query_clients(&ClientsQuery::new(&realm, &config.client))
+        .await_err_as_failed_dependency()
+        .await?
+        .into_iter()
+        .find(|client| client.client_id == config.client)
+        .ok_or(HttpAppErr::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "client not found",
+        ))
+}
+
+fn read_profile(
+    account: &AccountType,
+    mut fields: BTreeMap<String, Value>,
+) -> Result<BTreeMap<String, String>, HttpAppErr> {
+    let mut validator = Validator::new();
+    let mut profile = BTreeMap::new();
+
+    for field in account.profile_fields.iter() {
+        let value = match fields.remove(field) {
+            Some(Value::String(value)) => value.trim().to_owned(),
+            Some(_) => {
+                validator.error(field, "must be a string");
+                continue;
+            }
+            None => String::new(),
+        };
+
+        validator
+            .required(field, &value)
+            .length(field, &value, 1, PROFILE_FIELD_MAX_LENGTH);
+        profile.insert(field.clone(), value);
+    }
+
+    validator.finish()?;
+    Ok(profile)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RegistrationRequest {
+    pub email: String,
+    pub password: String,
+    #[serde(flatten)]
+    pub profile: BTreeMap<String, Value>,
+}
+
+impl Validate for RegistrationRequest {
+    fn validate(&self, validator: &mut Validator) {
+        validator
+            .required("email", &self.email)
+            .email("email", &self.email)
+            .required("password", &self.password)
+            .password("password", &self.password);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, sync::Arc};
+
+    use axum::{Extension, body::Body, http::Request};
+    use http::{Method, StatusCode, header::AUTHORIZATION};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::{
+        config::{AccountConfig, AuthConfig, TenantConfig},
+        kafka::{
+            outbox::{EventOutbox, FailingOutbox},
+            outbox_implementation::SqliteEventOutbox,
+        },
+        keycloak::{
+            mock::server::{MockKeycloak, TEST_CLIENT, TEST_REALM, test_realm_description},
+            services::{
+                realm_description::RoleDescription, seeding::KeycloakSeeding,
+                seeding_implementation::DefaultKeycloakSeeding,
+            },
+        },
+        tenant::TENANT_HEADER,
+    };
+
+    use super::create_registration_router;
+
+    async fn start_seeded_keycloak() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+
+        let mut description = test_realm_description();
+        description.clients[0].roles.push(RoleDescription {
+            name: "courier".to_owned(),
+            description: "store courier".to_owned(),
+            composites: vec![],
+        });
+
+        DefaultKeycloakSeeding::new(keycloak.create_manager())
+            .seed(description)
+            .await
+            .unwrap();
+
+        keycloak
+    }
+
+    fn config_with_courier(keycloak: &MockKeycloak) -> Arc<AuthConfig> {
+        let mut config = keycloak.create_config();
+
+        let mut courier = AccountConfig::new("courier", "courier", "courier-created");
+        courier.profile_fields = vec!["phone".to_owned()];
+        Arc::get_mut(&mut config).unwrap().accounts.push(courier);
+
+        config
+    }
+
+    async fn post(
+        keycloak: &MockKeycloak,
+        config: Arc<AuthConfig>,
+        outbox: Arc<SqliteEventOutbox>,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_owned()))
+            .unwrap();
+
+        let response = create_registration_router(keycloak.create_manager(), outbox, config)
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    async fn register(
+        keycloak: &MockKeycloak,
+        outbox: Arc<SqliteEventOutbox>,
+        email: &str,
+    ) -> StatusCode {
+        let body = format!(r#"{{"email":"{email}","password":"Secret123"}}"#);
+        let (status, _) = post(
+            keycloak,
+            keycloak.create_config(),
+            outbox,
+            "/api/customers",
+            &body,
+        )
+        .await;
+
+        status
+    }
+
+    async fn upgrade<TOutbox>(
+        keycloak: &MockKeycloak,
+        outbox: Arc<TOutbox>,
+        token: &str,
+    ) -> StatusCode
+    where
+        TOutbox: EventOutbox + Send + Sync + 'static,
+    {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/vendors/upgrade")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+
+        create_registration_router(keycloak.create_manager(), outbox, keycloak.create_config())
+            .layer(Extension(keycloak.create_validator()))
+            .oneshot(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn user_roles(keycloak: &MockKeycloak, username: &str) -> Vec<String> {
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            let user = realm.user(username).unwrap();
+
+            realm
+                .user_client_roles(user, &realm.client(TEST_CLIENT).unwrap().id)
+                .into_iter()
+                .map(|role| role.name.clone())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        })
+    }
+
+    #[tokio::test]
+    async fn registers_customer_and_records_event() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let status = register(&keycloak, outbox.clone(), "jane@example.com").await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            assert!(realm.user("jane@example.com").unwrap().email_verified);
+        });
+        assert_eq!(user_roles(&keycloak, "jane@example.com"), ["customer"]);
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].topic, "customer-created");
+        assert_eq!(
+            pending[0].payload,
+            r#"{"email":"jane@example.com","tenant":"default"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn registers_vendor_with_vendor_role_and_topic() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let (status, _) = post(
+            &keycloak,
+            keycloak.create_config(),
+            outbox.clone(),
+            "/api/vendors",
+            r#"{"email":"shop@example.com","password":"Secret123"}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(user_roles(&keycloak, "shop@example.com"), ["vendor"]);
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(pending[0].topic, "vendor-created");
+    }
+
+    #[tokio::test]
+    async fn registers_configured_account_type_with_profile_fields() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let (status, _) = post(
+            &keycloak,
+            config_with_courier(&keycloak),
+            outbox.clone(),
+            "/api/couriers",
+            r#"{"email":"bob@example.com","password":"Secret123","phone":"+100200300"}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(user_roles(&keycloak, "bob@example.com"), ["courier"]);
+        keycloak.with_data(|data| {
+            let user = data
+                .realm(TEST_REALM)
+                .unwrap()
+                .user("bob@example.com")
+                .unwrap();
+            assert_eq!(user.attributes["phone"], ["+100200300"]);
+        });
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        assert_eq!(pending[0].topic, "courier-created");
+        let event: Value = serde_json::from_str(&pending[0].payload).unwrap();
+        assert_eq!(event["email"], "bob@example.com");
+        assert_eq!(event["phone"], "+100200300");
+    }
+
+    #[tokio::test]
+    async fn requires_configured_profile_fields() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let (status, body) = post(
+            &keycloak,
+            config_with_courier(&keycloak),
+            outbox.clone(),
+            "/api/couriers",
+            r#"{"email":"bob@example.com","password":"Secret123"}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["errors"]["phone"][0], "is required");
+        assert!(outbox.fetch_pending(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn upgrades_customer_to_vendor_and_records_event() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        register(&keycloak, outbox.clone(), "jane@example.com").await;
+        let token = keycloak.sign_in("jane@example.com");
+
+        let status = upgrade(&keycloak, outbox.clone(), &token).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            user_roles(&keycloak, "jane@example.com"),
+            ["customer", "vendor"]
+        );
+
+        let pending = outbox.unpublished().await;
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[1].topic, "vendor-created");
+        assert_eq!(pending[0].key, pending[1].key);
+    }
+
+    #[tokio::test]
+    async fn refuses_upgrade_for_users_without_customer_role() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        post(
+            &keycloak,
+            keycloak.create_config(),
+            outbox.clone(),
+            "/api/vendors",
+            r#"{"email":"shop@example.com","password":"Secret123"}"#,
+        )
+        .await;
+        let token = keycloak.sign_in("shop@example.com");
+
+        let status = upgrade(&keycloak, outbox.clone(), &token).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(outbox.fetch_pending(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn offers_upgrade_only_for_opted_in_account_types() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        register(&keycloak, outbox.clone(), "jane@example.com").await;
+        let token = keycloak.sign_in("jane@example.com");
+
+        let mut config = keycloak.create_config();
+        Arc::get_mut(&mut config)
+            .unwrap()
+            .accounts
+            .push(AccountConfig::new("courier", "courier", "courier-created"));
+        let router = create_registration_router(keycloak.create_manager(), outbox.clone(), config)
+            .layer(Extension(keycloak.create_validator()));
+
+        for uri in ["/api/customers/upgrade", "/api/couriers/upgrade"] {
+            let response = router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(uri)
+                        .header(AUTHORIZATION, format!("Bearer {token}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+
+        assert_eq!(user_roles(&keycloak, "jane@example.com"), ["customer"]);
+    }
+
+    #[tokio::test]
+    async fn removes_vendor_role_when_event_cannot_be_recorded() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        register(&keycloak, outbox.clone(), "jane@example.com").await;
+        let token = keycloak.sign_in("jane@example.com");
+
+        let status = upgrade(&keycloak, Arc::new(FailingOutbox), &token).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(user_roles(&keycloak, "jane@example.com"), ["customer"]);
+
+        let status = upgrade(&keycloak, outbox.clone(), &token).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(outbox.unpublished().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn registers_user_in_realm_of_requested_tenant() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let mut description = test_realm_description();
+        description.realm = "acme_realm".to_owned();
+        DefaultKeycloakSeeding::new(keycloak.create_manager())
+            .seed(description)
+            .await
+            .unwrap();
+
+        let mut config = keycloak.create_config();
+        Arc::get_mut(&mut config)
+            .unwrap()
+            .tenants
+            .push(TenantConfig::new("acme", "acme_realm"));
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/customers")
+            .header("Content-Type", "application/json")
+            .header(TENANT_HEADER, "acme")
+            .body(Body::from(
+                r#"{"email":"jane@example.com","password":"Secret123"}"#,
+            ))
+            .unwrap();
+
+        let response =
+            create_registration_router(keycloak.create_manager(), outbox.clone(), config)
+                .oneshot(request)
+                .await
+                .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        keycloak.with_data(|data| {
+            assert!(
+                data.realm("acme_realm")
+                    .unwrap()
+                    .user("jane@example.com")
+                    .is_some()
+            );
+            assert!(data.realm(TEST_REALM).unwrap().users.is_empty());
+        });
+
+        let pending = outbox.fetch_pending(10).await.unwrap();
+        let event: Value = serde_json::from_str(&pending[0].payload).unwrap();
+        assert_eq!(event["tenant"], "acme");
+    }
+
+    #[tokio::test]
+    async fn registers_unverified_user_and_sends_verification_email() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let mut config = keycloak.create_config();
+        Arc::get_mut(&mut config).unwrap().keycloak.verify_email = true;
+
+        let (status, _) = post(
+            &keycloak,
+            config,
+            outbox.clone(),
+            "/api/customers",
+            r#"{"email":"jane@example.com","password":"Secret123"}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CREATED);
+        keycloak.with_data(|data| {
+            let user = data
+                .realm(TEST_REALM)
+                .unwrap()
+                .user("jane@example.com")
+                .unwrap();
+            assert!(!user.email_verified);
+            assert_eq!(user.required_actions, ["VERIFY_EMAIL"]);
+            assert_eq!(user.sent_actions, ["VERIFY_EMAIL"]);
+        });
+        assert_eq!(outbox.fetch_pending(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rolls_back_user_when_role_assignment_fails() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        keycloak.fail(
+            Method::POST,
+            "/role-mappings/clients/",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        );
+
+        let status = register(&keycloak, outbox.clone(), "jane@example.com").await;
+
+        assert_eq!(status, StatusCode::FAILED_DEPENDENCY);
+        keycloak.with_data(|data| {
+            assert!(data.realm(TEST_REALM).unwrap().users.is_empty());
+        });
+        assert!(outbox.fetch_pending(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rolls_back_only_the_new_user_when_usernames_share_a_prefix() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let status = register(&keycloak, outbox.clone(), "jane@example.com.au").await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        keycloak.fail(
+            Method::POST,
+            "/role-mappings/clients/",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        );
+
+        let status = register(&keycloak, outbox.clone(), "jane@example.com").await;
+
+        assert_eq!(status, StatusCode::FAILED_DEPENDENCY);
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            assert_eq!(realm.users.len(), 1);
+
+            let existing = realm.user("jane@example.com.au").unwrap();
+            assert_eq!(existing.email.as_deref(), Some("jane@example.com.au"));
+        });
+        assert_eq!(user_roles(&keycloak, "jane@example.com.au"), ["customer"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_already_registered_email_with_conflict() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        register(&keycloak, outbox.clone(), "jane@example.com").await;
+        let status = register(&keycloak, outbox.clone(), "jane@example.com").await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+        keycloak.with_data(|data| {
+            assert!(
+                data.realm(TEST_REALM)
+                    .unwrap()
+                    .user("jane@example.com")
+                    .is_some()
+            );
+        });
+        assert_eq!(outbox.fetch_pending(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_request_with_field_errors() {
+        let keycloak = start_seeded_keycloak().await;
+        let outbox = Arc::new(SqliteEventOutbox::open(":memory:").unwrap());
+
+        let (status, body) = post(
+            &keycloak,
+            keycloak.create_config(),
+            outbox.clone(),
+            "/api/customers",
+            r#"{"email":"jane","password":"short"}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["errors"]["email"][0], "must be a valid email address");
+        assert_eq!(
+            body["errors"]["password"][0],
+            "must be at least 8 characters long"
+        );
+
+        keycloak.with_data(|data| {
+            assert!(data.realm(TEST_REALM).unwrap().users.is_empty());
+        });
+        assert!(outbox.fetch_pending(10).await.unwrap().is_empty());
+    }
+}
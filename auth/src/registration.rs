@@ -0,0 +1,333 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::response::IntoResponse;
+use derive_more::Display;
+use http::StatusCode;
+use serde_json::{Map, Value};
+use utils::errors::HttpAppErr;
+
+use crate::{
+    config::{AccountConfig, AuthConfig},
+    kafka::outbox::{EventOutbox, OutboxMessage},
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        services::{
+            management::KeycloakManagement,
+            queries::users::UsersQuery,
+            requests::{
+                assign_roles::{AssignRoleRequest, AssignRolesRequest},
+                create_user::CreateUserRequest,
+                delete_user::DeleteUserRequest,
+                execute_actions_email::{ExecuteActionsEmailRequest, VERIFY_EMAIL_ACTION},
+                update_users_email_request::UpdateUsersEmailRequest,
+            },
+            responses::{client::ClientResponse, role::RoleResponse},
+        },
+    },
+};
+
+#[derive(Display, Clone, Copy)]
+pub enum RegistrationStep {
+    #[display("create user")]
+    CreateUser,
+    #[display("query user")]
+    QueryUser,
+    #[display("update email")]
+    UpdateEmail,
+    #[display("assign roles")]
+    AssignRoles,
+    #[display("record event")]
+    RecordEvent,
+}
+
+pub struct NewAccount {
+    pub tenant: String,
+    pub email: String,
+    pub profile: BTreeMap<String, String>,
+}
+
+pub type EventBuilder = fn(&NewAccount) -> Value;
+
+pub struct AccountType {
+    pub name: String,
+    pub path: String,
+    pub role: String,
+    pub topic: String,
+    pub profile_fields: Vec<String>,
+    pub upgrade_from: Option<String>,
+    pub event: EventBuilder,
+}
+
+impl AccountType {
+    pub fn new(config: &AccountConfig) -> Self {
+        AccountType {
+            name: config.name.clone(),
+            path: config.path.clone(),
+            role: config.role.clone(),
+            topic: config.topic.clone(),
+            profile_fields: config.profile_fields.clone(),
+            upgrade_from: config.upgrade_from.clone(),
+            event: account_created_event,
+        }
+    }
+
+    pub fn with_event(self, event: EventBuilder) -> Self {
+        AccountType { event, ..self }
+    }
+}
+
+fn account_created_event(account: &NewAccount) -> Value {
+    let mut payload = Map::new();
+    payload.insert("tenant".to_owned(), Value::from(account.tenant.clone()));
+    payload.insert("email".to_owned(), Value::from(account.email.clone()));
+
+    for (field, value) in account.profile.iter() {
+        payload.insert(field.clone(), Value::from(value.clone()));
+    }
+
+    Value::Object(payload)
+}
+
+pub struct RegistrationState<TManager, TOutbox> {
+    pub manager: Arc<TManager>,
+    pub outbox: Arc<TOutbox>,
+    pub config: Arc<AuthConfig>,
+    pub account: Arc<AccountType>,
+}
+
+impl<TManager, TOutbox> Clone for RegistrationState<TManager, TOutbox> {
+    fn clone(&self) -> Self {
+        RegistrationState {
+            manager: self.manager.clone(),
+            outbox: self.outbox.clone(),
+            config: self.config.clone(),
+            account: self.account.clone(),
+        }
+    }
+}
+
+pub struct RegistrationFailure {
+    pub step: RegistrationStep,
+    pub err: HttpAppErr,
+}
+
+impl From<RegistrationFailure> for HttpAppErr {
+    fn from(value: RegistrationFailure) -> Self {
+        if value.err.status == StatusCode::CONFLICT {
+            return HttpAppErr::new(
+                StatusCode::CONFLICT,
+                "an account with this email already exists",
+            );
+        }
+
+        HttpAppErr::new(
+            value.err.status,
+            &format!(
+                "registration failed at {0} step: {1}",
+                value.step, value.err.reason
+            ),
+        )
+    }
+}
+
+impl IntoResponse for RegistrationFailure {
+    fn into_response(self) -> axum::response::Response {
+        let err: HttpAppErr = self.into();
+        err.into_response()
+    }
+}
+
+pub struct RegistrationSaga<'a, TManager: KeycloakManagement> {
+    manager: &'a TManager,
+    realm: String,
+    email: String,
+    attributes: BTreeMap<String, Vec<String>>,
+    verify_email: bool,
+    user_created: bool,
+    user_id: Option<String>,
+}
+
+impl<'a, TManager> RegistrationSaga<'a, TManager>
+where
+    TManager: KeycloakManagement + Send + Sync,
+{
+    pub fn new(manager: &'a TManager, realm: &str, email: &str) -> Self {
+        RegistrationSaga {
+            manager,
+            realm: realm.to_owned(),
+            email: email.to_owned(),
+            attributes: BTreeMap::new(),
+            verify_email: false,
+            user_created: false,
+            user_id: None,
+        }
+    }
+
+    pub fn with_profile(mut self, profile: &BTreeMap<String, String>) -> Self {
+        self.attributes = profile
+            .iter()
+            .map(|(field, value)| (field.clone(), vec![value.clone()]))
+            .collect();
+        self
+    }
+
+    pub fn with_email_verification(mut self, verify_email: bool) -> Self {
+        self.verify_email = verify_email;
+        self
+    }
+
+    pub async fn run(
+        mut self,
+        password: &str,
+        client: &ClientResponse,
+        role: &RoleResponse,
+        outbox: &(impl EventOutbox + Sync),
+        event: &OutboxMessage,
+    ) -> Result<String, RegistrationFailure> {
+        match self.execute(password, client, role, outbox, event).await {
+            Ok(user_id) => {
+                if self.verify_email {
+                    self.send_verification_email(&user_id).await;
+                }
+                Ok(user_id)
+            }
+            Err(failure) => {
+                log::warn!(
+                    "registration failed at {0} step, rolling back",
+                    failure.step
+                );
+                self.compensate().await;
+                Err(failure)
+            }
+        }
+    }
+
+    async fn execute(
+        &mut self,
+        password: &str,
+        client: &ClientResponse,
+        role: &RoleResponse,
+        outbox: &(impl EventOutbox + Sync),
+        event: &OutboxMessage,
+    ) -> Result<String, RegistrationFailure> {
+        let mut request = CreateUserRequest::new(&self.realm, &self.email, &password);
+        request.attributes = self.attributes.clone();
+        if self.verify_email {
+            request.required_actions = vec![VERIFY_EMAIL_ACTION.to_owned()];
+        }
+
+        self.manager
+            .create_user(&request)
+            .await_err_as_failed_dependency()
+            .await
+            .map_err(|err| failed_at(RegistrationStep::CreateUser, err))?;
+
+        self.user_created = true;
+
+        let user_id = self
+            .find_user_id()
+            .await
+            .map_err(|err| failed_at(RegistrationStep::QueryUser, err))?;
+
+        self.user_id = Some(user_id.clone());
+
+        let email_request = if self.verify_email {
+            UpdateUsersEmailRequest::new_unverified(&self.realm, &user_id, &self.email)
+        } else {
+            UpdateUsersEmailRequest::new_verified(&self.realm, &user_id, &self.email)
+        };
+
+        self.manager
+            .update_users_email(&email_request)
+            .await_err_as_failed_dependency()
+            .await
+            .map_err(|err| failed_at(RegistrationStep::UpdateEmail, err))?;
+
+        self.manager
+            .assign_roles(&AssignRolesRequest::new(
+                &self.realm,
+                &user_id,
+                &client.id,
+                &[AssignRoleRequest::new(&role.id, &role.name)],
+            ))
+            .await_err_as_failed_dependency()
+            .await
+            .map_err(|err| failed_at(RegistrationStep::AssignRoles, err))?;
+
+        outbox.enqueue(event).await.map_err(|err| {
+            failed_at(
+                RegistrationStep::RecordEvent,
+                HttpAppErr::from(StatusCode::INTERNAL_SERVER_ERROR, err),
+            )
+        })?;
+
+        Ok(user_id)
+    }
+
+    async fn find_user_id(&self) -> Result<String, HttpAppErr> {
+        let users = self
+            .manager
+            .query_users(&UsersQuery::new(&self.realm, &self.email))
+            .await_err_as_failed_dependency()
+            .await?;
+
+        users
+            .into_iter()
+            .find(|user| user.username.eq_ignore_ascii_case(&self.email))
+            .map(|user| user.id)
+            .ok_or(HttpAppErr::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to create user",
+            ))
+    }
+
+    async fn send_verification_email(&self, user_id: &str) {
+        match self
+            .manager
+            .execute_actions_email(&ExecuteActionsEmailRequest::new_verify_email(
+                &self.realm,
+                &user_id,
+            ))
+            .log_err()
+            .await
+        {
+            Ok(()) => log::info!("verification email sent to user {user_id}"),
+            Err(err) => log::warn!("cannot send verification email to user {user_id}: {err}"),
+        }
+    }
+
+    async fn compensate(&self) {
+        if !self.user_created {
+            return;
+        }
+
+        let user_id = match &self.user_id {
+            Some(user_id) => user_id.clone(),
+            None => match self.find_user_id().await {
+                Ok(user_id) => user_id,
+                Err(err) => {
+                    log::error!(
+                        "cannot roll back user {0}, user not found: {1}",
+                        self.email,
+                        err.reason
+                    );
+                    return;
+                }
+            },
+        };
+
+        match self
+            .manager
+            .delete_user(&DeleteUserRequest::new(&self.realm, &user_id))
+            .log_err()
+            .await
+        {
+            Ok(()) => log::info!("user {0} rolled back", self.email),
+            Err(err) => log::error!("cannot roll back user {0}: {err}", self.email),
+        }
+    }
+}
+
+fn failed_at(step: RegistrationStep, err: HttpAppErr) -> RegistrationFailure {
+    RegistrationFailure { step, err }
+}
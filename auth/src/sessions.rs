@@ -0,0 +1,272 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::State,
+    response::{IntoResponse, Result},
+    routing::get,
+};
+use http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use utils::jwt::AuthUser;
+
+use crate::{
+    config::AuthConfig,
+    keycloak::{
+        keycloak_ex::KeycloakExtensions,
+        services::{
+            management::KeycloakManagement, queries::user_sessions::UserSessionsQuery,
+            requests::logout_user::LogoutUserRequest,
+        },
+    },
+    tenant::resolve_tenant,
+};
+
+pub struct SessionsState<TManager> {
+    pub manager: Arc<TManager>,
+    pub config: Arc<AuthConfig>,
+}
+
+impl<TManager> Clone for SessionsState<TManager> {
+    fn clone(&self) -> Self {
+        SessionsState {
+            manager: self.manager.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+pub fn create_sessions_router<TManager>(manager: Arc<TManager>, config: Arc<AuthConfig>) -> Router
+where
+    TManager: KeycloakManagement + Send + Sync + 'static,
+{
+    Router::new()
+        .route(
+            "/api/sessions",
+            get(list_sessions::<TManager>).delete(revoke_sessions::<TManager>),
+        )
+        .with_state(SessionsState { manager, config })
+}
+
+async fn list_sessions<TManager>(
+    State(state): State<SessionsState<TManager>>,
+    headers: HeaderMap,
+    user: AuthUser,
+) -> Result<SessionsResponse>
+where
+    TManager: KeycloakManagement + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    let sessions = state
+        .manager
+        .query_user_sessions(&UserSessionsQuery::new(realm_name, &user.id))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    Ok(SessionsResponse(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse {
+                id: session.id,
+                ip_address: session.ip_address,
+                started: session.start,
+                last_access: session.last_access,
+                clients: session.clients.into_values().collect(),
+            })
+            .collect(),
+    ))
+}
+
+async fn revoke_sessions<TManager>(
+    State(state): State<SessionsState<TManager>>,
+    headers: HeaderMap,
+    user: AuthUser,
+) -> Result<StatusCode>
+where
+    TManager: KeycloakManagement + Send + Sync,
+{
+    let tenant = resolve_tenant(&state.config, &headers)?;
+    let realm_name = &tenant.realm;
+
+    state
+        .manager
+        .logout_user(&LogoutUserRequest::new(realm_name, &user.id))
+        .await_err_as_failed_dependency()
+        .await?;
+
+    log::info!("all sessions of user {0} revoked", user.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize)]
+struct SessionResponse {
+    pub id: String,
+    #[serde(rename = "ipAddress")]
+    pub ip_address: Option<String>,
+    pub started: i64,
+    #[serde(rename = "lastAccess")]
+    pub last_access: i64,
+    pub clients: Vec<String>,
+}
+
+struct SessionsResponse(Vec<SessionResponse>);
+
+impl IntoResponse for SessionsResponse {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self.0)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Extension, Router, body::Body, http::Request};
+    use http::{Method, StatusCode, header::AUTHORIZATION};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::keycloak::{
+        mock::server::{MockKeycloak, TEST_REALM, test_realm_description},
+        services::{
+            management::KeycloakManagement, requests::create_user::CreateUserRequest,
+            seeding::KeycloakSeeding, seeding_implementation::DefaultKeycloakSeeding,
+        },
+    };
+
+    use super::create_sessions_router;
+
+    async fn start_keycloak_with_users() -> MockKeycloak {
+        let keycloak = MockKeycloak::start().await;
+        let manager = keycloak.create_manager();
+
+        DefaultKeycloakSeeding::new(manager.clone())
+            .seed(test_realm_description())
+            .await
+            .unwrap();
+
+        for username in ["jane@example.com", "john@example.com"] {
+            manager
+                .create_user(&CreateUserRequest::new(
+                    &TEST_REALM,
+                    &username,
+                    &"Secret123",
+                ))
+                .await
+                .unwrap();
+        }
+
+        keycloak
+    }
+
+    fn sessions_router(keycloak: &MockKeycloak) -> Router {
+        create_sessions_router(keycloak.create_manager(), keycloak.create_config())
+            .layer(Extension(keycloak.create_validator()))
+    }
+
+    fn sessions_request(method: Method, token: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri("/api/sessions")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn session_ids(keycloak: &MockKeycloak, username: &str) -> Vec<String> {
+        keycloak.with_data(|data| {
+            let realm = data.realm(TEST_REALM).unwrap();
+            let user_id = &realm.user(username).unwrap().id;
+            let mut ids: Vec<String> = realm
+                .sessions
+                .iter()
+                .filter(|(_, owner)| *owner == user_id)
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.sort();
+            ids
+        })
+    }
+
+    #[tokio::test]
+    async fn lists_only_sessions_of_current_user() {
+        let keycloak = start_keycloak_with_users().await;
+        keycloak.sign_in("jane@example.com");
+        let token = keycloak.sign_in("jane@example.com");
+        keycloak.sign_in("john@example.com");
+
+        let response = sessions_router(&keycloak)
+            .oneshot(sessions_request(Method::GET, &token))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let sessions: Vec<Value> = serde_json::from_slice(&body).unwrap();
+
+        let mut ids: Vec<String> = sessions
+            .iter()
+            .map(|session| session["id"].as_str().unwrap().to_owned())
+            .collect();
+        ids.sort();
+
+        assert_eq!(ids, session_ids(&keycloak, "jane@example.com"));
+        assert_eq!(ids.len(), 2);
+        assert_eq!(sessions[0]["ipAddress"], "127.0.0.1");
+        assert!(sessions[0]["lastAccess"].is_i64());
+        assert!(sessions[0]["clients"].is_array());
+    }
+
+    #[tokio::test]
+    async fn revokes_all_sessions_of_current_user() {
+        let keycloak = start_keycloak_with_users().await;
+        keycloak.sign_in("jane@example.com");
+        let token = keycloak.sign_in("jane@example.com");
+        keycloak.sign_in("john@example.com");
+
+        let response = sessions_router(&keycloak)
+            .oneshot(sessions_request(Method::DELETE, &token))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(session_ids(&keycloak, "jane@example.com").is_empty());
+        assert_eq!(session_ids(&keycloak, "john@example.com").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn reports_keycloak_failure_as_failed_dependency() {
+        let keycloak = start_keycloak_with_users().await;
+        let token = keycloak.sign_in("jane@example.com");
+        keycloak.fail(Method::POST, "/logout", StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = sessions_router(&keycloak)
+            .oneshot(sessions_request(Method::DELETE, &token))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FAILED_DEPENDENCY);
+        assert_eq!(session_ids(&keycloak, "jane@example.com").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_request_without_token() {
+        let keycloak = start_keycloak_with_users().await;
+
+        let response = sessions_router(&keycloak)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/sessions")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,144 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use http::{HeaderMap, StatusCode, header::HOST};
+use utils::{errors::HttpAppErr, jwt::JwtValidator};
+
+use crate::config::{AuthConfig, TenantConfig};
+
+pub const TENANT_HEADER: &str = "X-Tenant-Id";
+
+pub fn resolve_tenant<'a>(
+    config: &'a AuthConfig,
+    headers: &HeaderMap,
+) -> Result<&'a TenantConfig, HttpAppErr> {
+    let header = headers
+        .get(TENANT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+
+    if let Some(id) = header {
+        return config.tenant(id).ok_or_else(|| {
+            HttpAppErr::new(StatusCode::NOT_FOUND, &format!("unknown tenant {id}"))
+        });
+    }
+
+    let subdomain = headers
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .and_then(subdomain);
+
+    Ok(subdomain
+        .and_then(|id| config.tenant(id))
+        .unwrap_or(config.default_tenant()))
+}
+
+fn subdomain(host: &str) -> Option<&str> {
+    let host = host.split(':').next()?;
+    let mut labels = host.split('.');
+    let first = labels.next()?;
+
+    (labels.count() >= 2).then_some(first)
+}
+
+pub struct TenantValidators {
+    config: Arc<AuthConfig>,
+    validators: HashMap<String, Arc<JwtValidator>>,
+}
+
+impl TenantValidators {
+    pub fn new(config: Arc<AuthConfig>, validators: HashMap<String, Arc<JwtValidator>>) -> Self {
+        TenantValidators { config, validators }
+    }
+}
+
+pub async fn select_tenant_validator(
+    State(validators): State<Arc<TenantValidators>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let validator = resolve_tenant(&validators.config, request.headers())
+        .ok()
+        .and_then(|tenant| validators.validators.get(&tenant.id))
+        .cloned();
+
+    if let Some(validator) = validator {
+        request.extensions_mut().insert(validator);
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue, StatusCode, header::HOST};
+
+    use crate::{
+        config::{AuthConfig, TenantConfig},
+        keycloak::mock::server::create_test_config,
+    };
+
+    use super::{TENANT_HEADER, resolve_tenant};
+
+    fn config_with_tenants() -> AuthConfig {
+        let mut config = create_test_config("http://127.0.0.1:8080", "admin", "admin");
+        config.tenants.push(TenantConfig::new("acme", "acme_realm"));
+        config
+            .tenants
+            .push(TenantConfig::new("globex", "globex_realm"));
+
+        config
+    }
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn resolves_tenant_from_header_before_subdomain() {
+        let config = config_with_tenants();
+
+        let mut request_headers = headers(TENANT_HEADER, "globex");
+        request_headers.insert(HOST, HeaderValue::from_static("acme.store.local"));
+
+        let tenant = resolve_tenant(&config, &request_headers).ok().unwrap();
+        assert_eq!(tenant.realm, "globex_realm");
+
+        let tenant = resolve_tenant(&config, &headers("host", "acme.store.local:5001"))
+            .ok()
+            .unwrap();
+        assert_eq!(tenant.realm, "acme_realm");
+    }
+
+    #[test]
+    fn falls_back_to_default_tenant() {
+        let config = config_with_tenants();
+        let default_realm = &config.default_tenant().realm;
+
+        for request_headers in [
+            HeaderMap::new(),
+            headers("host", "localhost:5001"),
+            headers("host", "shop.store.local"),
+        ] {
+            let tenant = resolve_tenant(&config, &request_headers).ok().unwrap();
+            assert_eq!(&tenant.realm, default_realm);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_tenant_header() {
+        let config = config_with_tenants();
+
+        let err = resolve_tenant(&config, &headers(TENANT_HEADER, "initech"))
+            .err()
+            .unwrap();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+}
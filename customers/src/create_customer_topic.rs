@@ -1,35 +1,46 @@
+use std::sync::Arc;
+
 use futures::TryFutureExt;
 use sea_orm::{ActiveModelTrait, ActiveValue::Set};
 use serde::Deserialize;
 use utils::{
-    env::env_var,
     errors::AppErr,
     kafka_consumer::{KafkaTopic, KafkaTopicDescriptor},
+    tenant::default_tenant,
 };
 
-use crate::db_factory::get_db_conn;
+use crate::{config::CustomersConfig, db_factory::get_db_conn};
 
-pub struct CreateCustomerTopic;
-impl KafkaTopic for CreateCustomerTopic {
-    fn get_descriptor() -> Result<utils::kafka_consumer::KafkaTopicDescriptor, AppErr> {
-        let descriptor = KafkaTopicDescriptor {
-            host: env_var("KAFKA_HOST")?,
-            topic: env_var("KAFKA_CUSTOMER_TOPIC")?,
-        };
+pub struct CreateCustomerTopic {
+    config: Arc<CustomersConfig>,
+}
 
-        Ok(descriptor)
+impl CreateCustomerTopic {
+    pub fn new(config: Arc<CustomersConfig>) -> Self {
+        CreateCustomerTopic { config }
+    }
+}
+
+impl KafkaTopic for CreateCustomerTopic {
+    fn get_descriptor(&self) -> KafkaTopicDescriptor {
+        KafkaTopicDescriptor {
+            host: self.config.kafka_host.clone(),
+            topic: self.config.customer_topic.clone(),
+            consumer: self.config.kafka_consumer.clone(),
+        }
     }
 
-    async fn handle_message(payload: &[u8]) -> Result<(), AppErr> {
+    async fn handle_message(&self, payload: &[u8]) -> Result<(), AppErr> {
         let event = serde_json::from_slice::<CreateCustomerEvent>(payload)
             .map_err(|err| AppErr::from_owned(format!("failed at serialization: {err}")))?;
 
         let customer = crate::entity::customer::ActiveModel {
+            tenant: Set(event.tenant),
             email: Set(event.email),
             ..Default::default()
         };
 
-        let db = get_db_conn().await?;
+        let db = get_db_conn(&self.config.db_host).await?;
         customer
             .insert(&db)
             .map_err(|err| AppErr::from_owned(format!("failed to create customer: {err}")))
@@ -42,5 +53,7 @@ impl KafkaTopic for CreateCustomerTopic {
 
 #[derive(Deserialize)]
 struct CreateCustomerEvent {
+    #[serde(default = "default_tenant")]
+    pub tenant: String,
     pub email: String,
 }
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use futures::TryFutureExt;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DeleteMany, EntityTrait, QueryFilter,
+    sea_query::{Expr, Func},
+};
+use serde::Deserialize;
+use utils::{
+    errors::AppErr,
+    kafka_consumer::{KafkaTopic, KafkaTopicDescriptor},
+    tenant::default_tenant,
+};
+
+use crate::{
+    config::CustomersConfig,
+    entity::customer::{Column, Entity},
+};
+
+pub struct DeleteUserTopic {
+    db: DatabaseConnection,
+    config: Arc<CustomersConfig>,
+}
+
+impl DeleteUserTopic {
+    pub fn new(db: DatabaseConnection, config: Arc<CustomersConfig>) -> Self {
+        DeleteUserTopic { db, config }
+    }
+}
+
+impl KafkaTopic for DeleteUserTopic {
+    fn get_descriptor(&self) -> KafkaTopicDescriptor {
+        KafkaTopicDescriptor {
+            host: self.config.kafka_host.clone(),
+            topic: self.config.user_deleted_topic.clone(),
+            consumer: self.config.kafka_consumer.clone(),
+        }
+    }
+
+    async fn handle_message(&self, payload: &[u8]) -> Result<(), AppErr> {
+        let event = serde_json::from_slice::<UserDeletedEvent>(payload)
+            .map_err(|err| AppErr::from_owned(format!("failed at serialization: {err}")))?;
+
+        let result = delete_user_rows(&event)
+            .exec(&self.db)
+            .map_err(|err| AppErr::from_owned(format!("failed to delete customer: {err}")))
+            .await?;
+
+        log::info!(
+            "user {0} deleted, {1} customer rows removed",
+            event.id,
+            result.rows_affected
+        );
+        Ok(())
+    }
+}
+
+fn delete_user_rows(event: &UserDeletedEvent) -> DeleteMany<Entity> {
+    Entity::delete_many()
+        .filter(Column::Tenant.eq(&event.tenant))
+        // rows stored before registration lowercased emails may still be mixed case
+        .filter(Expr::expr(Func::lower(Expr::col(Column::Email))).eq(event.email.to_lowercase()))
+}
+
+#[derive(Deserialize)]
+struct UserDeletedEvent {
+    #[serde(default = "default_tenant")]
+    pub tenant: String,
+    pub id: String,
+    pub email: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sea_orm::{DatabaseBackend, DatabaseConnection, QueryTrait};
+    use utils::{
+        jwt::JwtValidatorSettings,
+        kafka_consumer::{KafkaConsumerSettings, KafkaTopic},
+        readiness::ReadinessSettings,
+    };
+
+    use crate::config::CustomersConfig;
+
+    use super::{DeleteUserTopic, UserDeletedEvent, delete_user_rows};
+
+    fn delete_sql(payload: &str) -> String {
+        let event = serde_json::from_str::<UserDeletedEvent>(payload).unwrap();
+
+        delete_user_rows(&event)
+            .build(DatabaseBackend::Postgres)
+            .to_string()
+    }
+
+    #[test]
+    fn deletes_customer_rows_of_user_in_tenant() {
+        assert_eq!(
+            delete_sql(r#"{"tenant":"acme","id":"42","email":"jane@example.com"}"#),
+            r#"DELETE FROM "customers" WHERE "customers"."tenant" = 'acme' AND LOWER("email") = 'jane@example.com'"#
+        );
+    }
+
+    #[test]
+    fn matches_email_case_insensitively() {
+        assert_eq!(
+            delete_sql(r#"{"tenant":"acme","id":"42","email":"Jane@Example.com"}"#),
+            r#"DELETE FROM "customers" WHERE "customers"."tenant" = 'acme' AND LOWER("email") = 'jane@example.com'"#
+        );
+    }
+
+    #[test]
+    fn falls_back_to_default_tenant() {
+        assert_eq!(
+            delete_sql(r#"{"id":"42","email":"jane@example.com"}"#),
+            r#"DELETE FROM "customers" WHERE "customers"."tenant" = 'default' AND LOWER("email") = 'jane@example.com'"#
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_events() {
+        let topic = DeleteUserTopic::new(
+            DatabaseConnection::Disconnected,
+            Arc::new(CustomersConfig {
+                service_host: "127.0.0.1:0".to_owned(),
+                db_host: "postgres://localhost/customers".to_owned(),
+                kafka_host: "127.0.0.1:9092".to_owned(),
+                customer_topic: "customer-created".to_owned(),
+                user_deleted_topic: "user-deleted".to_owned(),
+                kafka_consumer: KafkaConsumerSettings::new("customers_group"),
+                jwt: JwtValidatorSettings {
+                    host: "http://127.0.0.1:8080".to_owned(),
+                    realms: vec!["test_realm".to_owned()],
+                    client: "app_client".to_owned(),
+                },
+                startup: ReadinessSettings::default(),
+            }),
+        );
+
+        let err = topic.handle_message(br#"{"id":"42"}"#).await.err().unwrap();
+
+        assert!(err.to_string().contains("failed at serialization"));
+    }
+}
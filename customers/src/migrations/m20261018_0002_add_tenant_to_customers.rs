@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(Iden)]
+enum Customers {
+    Table,
+    Tenant,
+    Email,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Customers::Table)
+                    .add_column(
+                        ColumnDef::new(Customers::Tenant)
+                            .string()
+                            .not_null()
+                            .char_len(64)
+                            .default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE customers DROP CONSTRAINT IF EXISTS customers_email_key",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_customers_tenant_email")
+                    .table(Customers::Table)
+                    .col(Customers::Tenant)
+                    .col(Customers::Email)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_customers_tenant_email")
+                    .table(Customers::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE customers ADD CONSTRAINT customers_email_key UNIQUE (email)",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Customers::Table)
+                    .drop_column(Customers::Tenant)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -1,12 +1,17 @@
 use sea_orm_migration::prelude::*;
 
-use crate::migrations::m20250331_0001_create_customers_table;
+use crate::migrations::{
+    m20250331_0001_create_customers_table, m20261018_0002_add_tenant_to_customers,
+};
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20250331_0001_create_customers_table::Migration)]
+        vec![
+            Box::new(m20250331_0001_create_customers_table::Migration),
+            Box::new(m20261018_0002_add_tenant_to_customers::Migration),
+        ]
     }
 }
@@ -1,2 +1,3 @@
 pub mod m20250331_0001_create_customers_table;
+pub mod m20261018_0002_add_tenant_to_customers;
 pub mod migrator;
\ No newline at end of file
@@ -0,0 +1,20 @@
+use utils::{
+    config::{ConfigReader, ServiceConfig},
+    jwt::JwtValidatorSettings,
+};
+
+pub struct GoodsConfig {
+    pub service_host: String,
+    pub db_host: Option<String>,
+    pub jwt: JwtValidatorSettings,
+}
+
+impl ServiceConfig for GoodsConfig {
+    fn read(reader: &mut ConfigReader) -> Self {
+        GoodsConfig {
+            service_host: reader.required("SERVICE_HOST"),
+            db_host: reader.optional("DB_HOST"),
+            jwt: JwtValidatorSettings::read(reader),
+        }
+    }
+}
@@ -1,19 +1,45 @@
-use axum::Router;
+pub mod config;
+pub mod scylla_health;
+
+use std::sync::Arc;
+
+use axum::{Extension, Router};
+use config::GoodsConfig;
 use futures::TryFutureExt;
-use utils::{dotenv::configure_dotenv, env::env_var, errors::AppErr, logging::configure_logs};
+use scylla_health::{ScyllaHealth, scylla_address};
+use utils::{
+    config::load_config,
+    dotenv::configure_dotenv,
+    errors::AppErr,
+    health::{HealthChecks, create_health_router},
+    http::{HttpClientSettings, create_http_client},
+    jwt::JwtValidator,
+    logging::configure_logs,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), AppErr> {
     configure_dotenv();
     _ = configure_logs(log::LevelFilter::Info)?;
 
-    let app = Router::new();
+    let config = load_config::<GoodsConfig>()?;
+
+    let validator = Arc::new(JwtValidator::new(
+        config.jwt.clone(),
+        Arc::new(create_http_client(&HttpClientSettings::new(
+            "web3store-goods",
+        ))?),
+    ));
 
-    let listener = tokio::net::TcpListener::bind(env_var("SERVICE_HOST")?)
+    let app = Router::new()
+        .merge(create_health_router(create_health_checks(&config)))
+        .layer(Extension(validator));
+
+    let listener = tokio::net::TcpListener::bind(&config.service_host)
         .map_err(|err| AppErr::from_owned(format!("failed to bind: {err}")))
         .await?;
 
-    log::info!("app started at: {0}", env_var("SERVICE_HOST")?);
+    log::info!("app started at: {0}", config.service_host);
 
     axum::serve(listener, app)
         .await
@@ -21,3 +47,16 @@ async fn main() -> Result<(), AppErr> {
 
     Ok(())
 }
+
+fn create_health_checks(config: &GoodsConfig) -> HealthChecks {
+    match &config.db_host {
+        Some(db_host) => {
+            let scylla = Arc::new(ScyllaHealth::new(&scylla_address(db_host)));
+            HealthChecks::new().with_check("scylla", move || {
+                let scylla = scylla.clone();
+                async move { scylla.ping().await }
+            })
+        }
+        None => HealthChecks::new(),
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use futures::TryFutureExt;
+use scylla::client::{session::Session, session_builder::SessionBuilder};
+use tokio::sync::OnceCell;
+use utils::errors::AppErr;
+
+const CQL_DEFAULT_PORT: u16 = 9042;
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn scylla_address(db_host: &str) -> String {
+    if db_host.contains(':') {
+        db_host.to_owned()
+    } else {
+        format!("{db_host}:{CQL_DEFAULT_PORT}")
+    }
+}
+
+// the session is opened by the first check and reused afterwards,
+// so the service can start while scylla is still coming up
+pub struct ScyllaHealth {
+    address: String,
+    session: OnceCell<Session>,
+}
+
+impl ScyllaHealth {
+    pub fn new(address: &str) -> Self {
+        ScyllaHealth {
+            address: address.to_owned(),
+            session: OnceCell::new(),
+        }
+    }
+
+    pub async fn ping(&self) -> Result<(), AppErr> {
+        let session = self
+            .session
+            .get_or_try_init(|| {
+                SessionBuilder::new()
+                    .known_node(&self.address)
+                    .connection_timeout(CONNECTION_TIMEOUT)
+                    .build()
+                    .map_err(|err| AppErr::from_owned(format!("scylla is unreachable: {err}")))
+            })
+            .await?;
+
+        session
+            .query_unpaged("SELECT release_version FROM system.local", ())
+            .map_err(|err| AppErr::from_owned(format!("failed to query scylla: {err}")))
+            .await?;
+
+        Ok(())
+    }
+}
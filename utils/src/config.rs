@@ -0,0 +1,267 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use super::errors::AppErr;
+
+pub trait ServiceConfig: Sized {
+    fn read(reader: &mut ConfigReader) -> Self;
+}
+
+pub struct ConfigSource {
+    env_values: HashMap<String, String>,
+    file_values: HashMap<String, String>,
+}
+
+impl ConfigSource {
+    pub fn new(env_values: HashMap<String, String>, file_values: HashMap<String, String>) -> Self {
+        ConfigSource {
+            env_values,
+            file_values,
+        }
+    }
+
+    pub fn load() -> Result<Self, AppErr> {
+        let file_values = match std::env::var("CONFIG_FILE") {
+            Ok(path) => std::fs::read_to_string(&path)
+                .map(|content| parse_config_file(&content))
+                .map_err(|err| {
+                    AppErr::from_owned(format!("failed to read config {path}: {err}"))
+                })?,
+            Err(_) => HashMap::new(),
+        };
+
+        Ok(ConfigSource::new(std::env::vars().collect(), file_values))
+    }
+
+    pub fn read<TConfig: ServiceConfig>(&self) -> Result<TConfig, AppErr> {
+        self.read_with(TConfig::read)
+    }
+
+    // for values whose keys are only known at runtime, e.g. secrets named in a realm file
+    pub fn read_with<T>(&self, read: impl FnOnce(&mut ConfigReader) -> T) -> Result<T, AppErr> {
+        let mut reader = ConfigReader {
+            source: self,
+            errors: vec![],
+        };
+
+        let config = read(&mut reader);
+
+        if reader.errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(AppErr::from_owned(format!(
+                "invalid configuration: {0}",
+                reader.errors.join("; ")
+            )))
+        }
+    }
+
+    fn value(&self, key: &str) -> Option<&String> {
+        self.env_values
+            .get(key)
+            .or_else(|| self.file_values.get(key))
+            .filter(|value| !value.is_empty())
+    }
+
+    fn lookup(&self, key: &str) -> Result<Option<String>, String> {
+        if let Some(value) = self.env_values.get(key).filter(|x| !x.is_empty()) {
+            return Ok(Some(value.clone()));
+        }
+
+        let file_key = format!("{key}_FILE");
+        if let Some(path) = self.value(&file_key) {
+            return std::fs::read_to_string(path)
+                .map(|secret| Some(secret.trim_end().to_owned()))
+                .map_err(|err| format!("{file_key} points to unreadable {path}: {err}"));
+        }
+
+        Ok(self.value(key).cloned())
+    }
+}
+
+pub struct ConfigReader<'a> {
+    source: &'a ConfigSource,
+    errors: Vec<String>,
+}
+
+impl ConfigReader<'_> {
+    pub fn optional(&mut self, key: &str) -> Option<String> {
+        self.source
+            .lookup(key)
+            .inspect_err(|err| self.errors.push(err.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn required(&mut self, key: &str) -> String {
+        match self.source.lookup(key) {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                self.errors.push(format!("{key} is missing"));
+                String::new()
+            }
+            Err(err) => {
+                self.errors.push(err);
+                String::new()
+            }
+        }
+    }
+
+    pub fn parsed_or<T>(&mut self, key: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.optional(key) {
+            Some(value) => value
+                .parse()
+                .inspect_err(|err| self.errors.push(format!("{key} is invalid: {err}")))
+                .unwrap_or(default),
+            None => default,
+        }
+    }
+
+    // for rules spanning several values, reported together with the missing and malformed ones
+    pub fn error(&mut self, message: &str) {
+        self.errors.push(message.to_owned());
+    }
+
+    pub fn parsed_list<T>(&mut self, key: &str) -> Vec<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.optional(key).unwrap_or_default();
+        let mut items = vec![];
+
+        for item in value.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            match item.parse() {
+                Ok(item) => items.push(item),
+                Err(err) => self
+                    .errors
+                    .push(format!("{key} has invalid item {item}: {err}")),
+            }
+        }
+
+        items
+    }
+}
+
+fn parse_config_file(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"');
+            (key.trim().to_owned(), value.to_owned())
+        })
+        .collect()
+}
+
+pub fn load_config<TConfig: ServiceConfig>() -> Result<TConfig, AppErr> {
+    ConfigSource::load()?.read()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{ConfigReader, ConfigSource, ServiceConfig, parse_config_file};
+
+    struct TestConfig {
+        host: String,
+        realm: String,
+        port: u16,
+        secret: Option<String>,
+    }
+
+    impl ServiceConfig for TestConfig {
+        fn read(reader: &mut ConfigReader) -> Self {
+            TestConfig {
+                host: reader.required("HOST"),
+                realm: reader.required("REALM"),
+                port: reader.parsed_or("PORT", 8080),
+                secret: reader.optional("SECRET"),
+            }
+        }
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let source = ConfigSource::new(
+            values(&[("HOST", "env-host")]),
+            values(&[("HOST", "file-host"), ("REALM", "file-realm")]),
+        );
+
+        let config = source.read::<TestConfig>().unwrap();
+
+        assert_eq!(config.host, "env-host");
+        assert_eq!(config.realm, "file-realm");
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.secret, None);
+    }
+
+    #[test]
+    fn parses_config_file() {
+        let values =
+            parse_config_file("# keycloak\nHOST = http://keycloak:8080\n\nREALM=\"store\"\n");
+
+        assert_eq!(values["HOST"], "http://keycloak:8080");
+        assert_eq!(values["REALM"], "store");
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn reports_every_missing_and_invalid_key() {
+        let source = ConfigSource::new(values(&[("PORT", "http")]), HashMap::new());
+
+        let err = source.read::<TestConfig>().err().unwrap().to_string();
+
+        assert!(err.contains("HOST is missing"));
+        assert!(err.contains("REALM is missing"));
+        assert!(err.contains("PORT is invalid"));
+    }
+
+    #[test]
+    fn parses_lists() {
+        let source = ConfigSource::new(values(&[("PORTS", "80, 443,,8080")]), HashMap::new());
+
+        let ports = source.read_with(|reader| reader.parsed_list::<u16>("PORTS"));
+        let missing = source.read_with(|reader| reader.parsed_list::<u16>("MISSING"));
+        let err = ConfigSource::new(values(&[("PORTS", "80,http")]), HashMap::new())
+            .read_with(|reader| reader.parsed_list::<u16>("PORTS"))
+            .err()
+            .unwrap();
+
+        assert_eq!(ports.ok().unwrap(), [80, 443, 8080]);
+        assert!(missing.ok().unwrap().is_empty());
+        assert!(err.to_string().contains("PORTS has invalid item http"));
+    }
+
+    #[test]
+    fn reads_secrets_from_file_indirection() {
+        let path = std::env::temp_dir().join(format!("config-secret-{0}", std::process::id()));
+        std::fs::write(&path, "s3cret\n").unwrap();
+
+        let source = ConfigSource::new(
+            values(&[
+                ("HOST", "host"),
+                ("REALM", "realm"),
+                ("SECRET_FILE", path.to_str().unwrap()),
+            ]),
+            HashMap::new(),
+        );
+
+        let config = source.read::<TestConfig>().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.secret.as_deref(), Some("s3cret"));
+    }
+}
@@ -1,22 +1,52 @@
+use std::collections::BTreeMap;
+
 use axum::{Json, response::IntoResponse};
 use derive_more::Display;
 use http::StatusCode;
 use serde::Serialize;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppErrKind {
+    Other,
+    Unauthorized,
+    NotFound,
+    Conflict,
+}
+
+impl AppErrKind {
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AppErrKind::Unauthorized,
+            StatusCode::NOT_FOUND => AppErrKind::NotFound,
+            StatusCode::CONFLICT => AppErrKind::Conflict,
+            _ => AppErrKind::Other,
+        }
+    }
+}
+
 #[derive(Display, Debug)]
+#[display("{msg}")]
 pub struct AppErr {
     msg: String,
+    kind: AppErrKind,
 }
 
 impl AppErr {
     pub fn from_owned(msg: String) -> Self {
-        AppErr { msg }
-    }
-    pub fn from(msg: &str) -> Self {
         AppErr {
-            msg: msg.to_owned(),
+            msg,
+            kind: AppErrKind::Other,
         }
     }
+    pub fn from(msg: &str) -> Self {
+        AppErr::from_owned(msg.to_owned())
+    }
+    pub fn with_kind(self, kind: AppErrKind) -> Self {
+        AppErr { kind, ..self }
+    }
+    pub fn kind(&self) -> AppErrKind {
+        self.kind
+    }
     pub fn cancelled<T>() -> Result<T, AppErr> {
         Result::<T, AppErr>::Err(AppErr::from("op cancelled"))
     }
@@ -25,6 +55,7 @@ impl AppErr {
 pub struct HttpAppErr {
     pub status: StatusCode,
     pub reason: String,
+    pub errors: BTreeMap<String, Vec<String>>,
 }
 
 impl HttpAppErr {
@@ -32,6 +63,7 @@ impl HttpAppErr {
         HttpAppErr {
             status,
             reason: reason.to_owned(),
+            errors: BTreeMap::new(),
         }
     }
 
@@ -39,6 +71,7 @@ impl HttpAppErr {
         HttpAppErr {
             status,
             reason: app_err.msg,
+            errors: BTreeMap::new(),
         }
     }
 
@@ -46,6 +79,15 @@ impl HttpAppErr {
         HttpAppErr {
             status: StatusCode::FAILED_DEPENDENCY,
             reason: err.msg,
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub fn validation(errors: BTreeMap<String, Vec<String>>) -> Self {
+        HttpAppErr {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            reason: "request validation failed".to_owned(),
+            errors,
         }
     }
 }
@@ -60,6 +102,7 @@ impl IntoResponse for HttpAppErr {
                 .to_owned(),
             status: self.status.as_u16(),
             reason: self.reason,
+            errors: self.errors,
         };
 
         (self.status, Json(error_msg)).into_response()
@@ -72,6 +115,7 @@ impl IntoResponse for AppErr {
             title: StatusCode::INTERNAL_SERVER_ERROR.as_str().to_owned(),
             status: 500,
             reason: "internal error".to_owned(),
+            errors: BTreeMap::new(),
         };
 
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_msg)).into_response()
@@ -84,6 +128,7 @@ impl From<HttpAppErr> for AppErr {
             "failed to perform http call: {0} {1}",
             value.status, value.reason
         ))
+        .with_kind(AppErrKind::from_status(value.status))
     }
 }
 
@@ -92,4 +137,6 @@ struct HttpErrorMessage {
     pub title: String,
     pub status: u16,
     pub reason: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<String>>,
 }
@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use axum::{
+    Json, Router,
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use futures::{FutureExt, future::BoxFuture};
+use http::StatusCode;
+use serde::Serialize;
+use tokio::time::{Instant, timeout};
+
+use crate::errors::AppErr;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+type HealthCheck = Box<dyn Fn() -> BoxFuture<'static, Result<(), AppErr>> + Send + Sync>;
+
+#[derive(Default)]
+pub struct HealthChecks {
+    checks: Vec<(String, HealthCheck)>,
+}
+
+impl HealthChecks {
+    pub fn new() -> Self {
+        HealthChecks::default()
+    }
+
+    pub fn with_check<TCheck, TFuture>(mut self, name: &str, check: TCheck) -> Self
+    where
+        TCheck: Fn() -> TFuture + Send + Sync + 'static,
+        TFuture: Future<Output = Result<(), AppErr>> + Send + 'static,
+    {
+        self.checks
+            .push((name.to_owned(), Box::new(move || check().boxed())));
+        self
+    }
+
+    async fn run(&self) -> HealthReport {
+        let results =
+            futures::future::join_all(self.checks.iter().map(|(name, check)| async move {
+                let started = Instant::now();
+
+                // details stay in the logs, the endpoint is public and only gets a generic reason
+                let error = match timeout(CHECK_TIMEOUT, check()).await {
+                    Ok(Ok(())) => None,
+                    Ok(Err(err)) => {
+                        log::warn!("health check {name} failed: {err}");
+                        Some("unavailable".to_owned())
+                    }
+                    Err(_) => {
+                        log::warn!("health check {name} timed out");
+                        Some("timed out".to_owned())
+                    }
+                };
+
+                let status = DependencyStatus {
+                    status: HealthStatus::from(error.is_none()),
+                    latency_ms: started.elapsed().as_millis(),
+                    error,
+                };
+
+                (name.clone(), status)
+            }))
+            .await;
+
+        HealthReport {
+            status: HealthStatus::from(results.iter().all(|(_, x)| x.status == HealthStatus::Up)),
+            dependencies: results.into_iter().collect(),
+        }
+    }
+}
+
+pub fn create_health_router(checks: HealthChecks) -> Router {
+    Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .route("/health", get(ready))
+        .with_state(Arc::new(checks))
+}
+
+async fn live() -> HealthReport {
+    HealthReport {
+        status: HealthStatus::Up,
+        dependencies: BTreeMap::new(),
+    }
+}
+
+async fn ready(State(checks): State<Arc<HealthChecks>>) -> HealthReport {
+    let report = checks.run().await;
+
+    if report.status == HealthStatus::Down {
+        log::warn!("readiness check failed");
+    }
+
+    report
+}
+
+#[derive(Serialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum HealthStatus {
+    Up,
+    Down,
+}
+
+impl From<bool> for HealthStatus {
+    fn from(value: bool) -> Self {
+        if value {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DependencyStatus {
+    pub status: HealthStatus,
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HealthReport {
+    pub status: HealthStatus,
+    pub dependencies: BTreeMap<String, DependencyStatus>,
+}
+
+impl IntoResponse for HealthReport {
+    fn into_response(self) -> Response {
+        let status = match self.status {
+            HealthStatus::Up => StatusCode::OK,
+            HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+        };
+
+        (status, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use http::StatusCode;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::errors::AppErr;
+
+    use super::{HealthChecks, create_health_router};
+
+    async fn get(checks: HealthChecks, uri: &str) -> (StatusCode, Value) {
+        let response = create_health_router(checks)
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn ready_reports_every_dependency() {
+        let checks = HealthChecks::new()
+            .with_check("database", || async { Ok(()) })
+            .with_check("broker", || async {
+                Err(AppErr::from(
+                    "kafka is unreachable: broker 10.0.0.7:9092 refused",
+                ))
+            });
+
+        let (status, body) = get(checks, "/health/ready").await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "down");
+        assert_eq!(body["dependencies"]["database"]["status"], "up");
+        assert!(body["dependencies"]["database"]["latencyMs"].is_u64());
+        assert_eq!(body["dependencies"]["broker"]["status"], "down");
+        assert_eq!(body["dependencies"]["broker"]["error"], "unavailable");
+    }
+
+    #[tokio::test]
+    async fn live_does_not_run_checks() {
+        let checks =
+            HealthChecks::new().with_check("broker", || async { Err(AppErr::from("down")) });
+
+        let (status, body) = get(checks, "/health/live").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "up");
+    }
+}
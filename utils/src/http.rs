@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, time::Duration};
 
 use futures::TryFutureExt;
 use reqwest::{Client, IntoUrl, Response};
@@ -6,7 +6,38 @@ use serde::{Serialize, de::DeserializeOwned};
 
 use crate::errors::HttpAppErr;
 
-use super::errors::AppErr;
+use super::errors::{AppErr, AppErrKind};
+
+pub struct HttpClientSettings {
+    pub user_agent: String,
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    pub pool_idle_timeout: Duration,
+    pub pool_max_idle_per_host: usize,
+}
+
+impl HttpClientSettings {
+    pub fn new(user_agent: &str) -> Self {
+        HttpClientSettings {
+            user_agent: user_agent.to_owned(),
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(30),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 16,
+        }
+    }
+}
+
+pub fn create_http_client(settings: &HttpClientSettings) -> Result<Client, AppErr> {
+    Client::builder()
+        .user_agent(&settings.user_agent)
+        .connect_timeout(settings.connect_timeout)
+        .timeout(settings.request_timeout)
+        .pool_idle_timeout(settings.pool_idle_timeout)
+        .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+        .build()
+        .map_err(|err| AppErr::from_owned(format!("failed to build http client: {err}")))
+}
 
 pub trait ResponseExtended {
     fn ensure_success(self) -> impl Future<Output = Result<(), AppErr>> + Send;
@@ -31,10 +62,10 @@ impl ResponseExtended for Response {
                 .ok()
                 .unwrap_or("".to_owned());
 
-            Err(AppErr::from_owned(format!(
-                "request {0} failed with {body}",
-                url
-            )))
+            Err(
+                AppErr::from_owned(format!("request {0} failed with {status} {body}", url))
+                    .with_kind(AppErrKind::from_status(status)),
+            )
         }
     }
 
@@ -79,9 +110,20 @@ pub trait SendExtended {
         url: impl IntoUrl + Display + Copy + Send,
         access_token: Option<impl Display + Send>,
     ) -> impl Future<Output = Result<Response, AppErr>> + Send;
+    fn quick_delete(
+        self,
+        url: impl IntoUrl + Display + Copy + Send,
+        access_token: Option<impl Display + Send>,
+    ) -> impl Future<Output = Result<Response, AppErr>> + Send;
+    fn quick_delete_with_body(
+        self,
+        url: impl IntoUrl + Display + Copy + Send,
+        body: &(impl Serialize + ?Sized + Send + Sync),
+        access_token: Option<impl Display + Send>,
+    ) -> impl Future<Output = Result<Response, AppErr>> + Send;
 }
 
-impl SendExtended for Client {
+impl SendExtended for &Client {
     async fn quick_get(
         self,
         url: impl IntoUrl + Display + Copy + Send,
@@ -136,4 +178,40 @@ impl SendExtended for Client {
             .await
             .map_err(|err| AppErr::from_owned(format!("put {0} failed with {err}", url)))
     }
+
+    async fn quick_delete(
+        self,
+        url: impl IntoUrl + Display + Copy + Send,
+        access_token: Option<impl Display + Send>,
+    ) -> Result<Response, AppErr> {
+        let method = self.delete(url);
+        let method_with_auth = match access_token {
+            Some(token) => method.bearer_auth(token),
+            None => method,
+        };
+
+        method_with_auth
+            .send()
+            .await
+            .map_err(|err| AppErr::from_owned(format!("delete {0} failed with {err}", url)))
+    }
+
+    async fn quick_delete_with_body(
+        self,
+        url: impl IntoUrl + Display + Copy + Send,
+        body: &(impl Serialize + ?Sized + Send + Sync),
+        access_token: Option<impl Display + Send>,
+    ) -> Result<Response, AppErr> {
+        let method = self.delete(url);
+        let method_with_auth = match access_token {
+            Some(token) => method.bearer_auth(token),
+            None => method,
+        };
+
+        method_with_auth
+            .json(body)
+            .send()
+            .await
+            .map_err(|err| AppErr::from_owned(format!("delete {0} failed with {err}", url)))
+    }
 }
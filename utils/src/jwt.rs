@@ -0,0 +1,466 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use futures::TryFutureExt;
+use http::{StatusCode, header::AUTHORIZATION};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::{
+    config::{ConfigReader, ServiceConfig},
+    errors::{AppErr, HttpAppErr},
+    http::ResponseExtended,
+};
+
+const JWKS_TTL: Duration = Duration::from_secs(300);
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct JwtValidatorSettings {
+    pub host: String,
+    // tokens issued by any of these realms are accepted, one realm per tenant
+    pub realms: Vec<String>,
+    pub client: String,
+}
+
+impl ServiceConfig for JwtValidatorSettings {
+    fn read(reader: &mut ConfigReader) -> Self {
+        let mut realms = vec![reader.required("KEYCLOAK_REALM")];
+
+        for id in reader.parsed_list::<String>("TENANTS") {
+            let key = id.to_uppercase().replace('-', "_");
+            realms.push(reader.required(&format!("TENANT_{key}_REALM")));
+        }
+
+        JwtValidatorSettings {
+            host: reader.required("KEYCLOAK_HOST"),
+            realms,
+            client: reader.required("KEYCLOAK_CLIENT"),
+        }
+    }
+}
+
+impl JwtValidatorSettings {
+    fn issuer(&self, realm: &str) -> String {
+        format!("{0}/realms/{realm}", self.host)
+    }
+
+    fn jwks_url(&self, realm: &str) -> String {
+        format!("{0}/protocol/openid-connect/certs", self.issuer(realm))
+    }
+
+    fn realm_of(&self, issuer: &str) -> Option<&str> {
+        self.realms
+            .iter()
+            .find(|realm| self.issuer(realm) == issuer)
+            .map(String::as_str)
+    }
+}
+
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+pub struct JwtValidator {
+    settings: JwtValidatorSettings,
+    client: Arc<Client>,
+    jwks: RwLock<HashMap<String, CachedJwks>>,
+}
+
+impl JwtValidator {
+    pub fn new(settings: JwtValidatorSettings, client: Arc<Client>) -> Self {
+        JwtValidator {
+            settings,
+            client,
+            jwks: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn validate(&self, token: &str) -> Result<AuthUser, HttpAppErr> {
+        let header =
+            decode_header(token).map_err(|err| unauthorized(&format!("malformed token: {err}")))?;
+
+        if header.alg != Algorithm::RS256 {
+            return Err(unauthorized("unsupported signing algorithm"));
+        }
+
+        let kid = header
+            .kid
+            .ok_or_else(|| unauthorized("token has no key id"))?;
+
+        let realm = self.issuing_realm(token)?;
+        let key = self.get_decoding_key(realm, &kid).await?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[self.settings.issuer(realm)]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+        validation.validate_aud = false;
+
+        let claims = decode::<AccessTokenClaims>(token, &key, &validation)
+            .map_err(|err| unauthorized(&format!("invalid token: {err}")))?
+            .claims;
+
+        if !claims.is_issued_for(&self.settings.client) {
+            return Err(unauthorized("token was issued for another audience"));
+        }
+
+        Ok(claims.into_user(&self.settings.client, token))
+    }
+
+    // the issuer is read before the signature is checked, only to pick the realm whose keys
+    // verify it, and the full validation below still rejects any other issuer
+    fn issuing_realm(&self, token: &str) -> Result<&str, HttpAppErr> {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.insecure_disable_signature_validation();
+        validation.set_required_spec_claims::<&str>(&[]);
+        validation.validate_exp = false;
+        validation.validate_aud = false;
+
+        let issuer = decode::<IssuerClaims>(token, &DecodingKey::from_secret(&[]), &validation)
+            .map_err(|err| unauthorized(&format!("malformed token: {err}")))?
+            .claims
+            .iss;
+
+        issuer
+            .as_deref()
+            .and_then(|issuer| self.settings.realm_of(issuer))
+            .ok_or_else(|| unauthorized("token was issued by an unknown realm"))
+    }
+
+    async fn get_decoding_key(&self, realm: &str, kid: &str) -> Result<DecodingKey, HttpAppErr> {
+        if let Some(key) = self.find_cached_key(realm, kid, JWKS_TTL).await? {
+            return Ok(key);
+        }
+
+        let mut cache = self.jwks.write().await;
+
+        let recently_fetched = cache
+            .get(realm)
+            .is_some_and(|cached| cached.fetched_at.elapsed() < JWKS_MIN_REFRESH_INTERVAL);
+
+        if !recently_fetched {
+            let keys = self
+                .fetch_jwks(realm)
+                .map_err(HttpAppErr::failed_dependency)
+                .await?;
+
+            cache.insert(
+                realm.to_owned(),
+                CachedJwks {
+                    keys,
+                    fetched_at: Instant::now(),
+                },
+            );
+        }
+
+        cache
+            .get(realm)
+            .and_then(|cached| cached.keys.find(kid))
+            .map(DecodingKey::from_jwk)
+            .transpose()
+            .map_err(|err| unauthorized(&format!("unusable signing key: {err}")))?
+            .ok_or_else(|| unauthorized("unknown signing key"))
+    }
+
+    async fn find_cached_key(
+        &self,
+        realm: &str,
+        kid: &str,
+        max_age: Duration,
+    ) -> Result<Option<DecodingKey>, HttpAppErr> {
+        let cache = self.jwks.read().await;
+
+        cache
+            .get(realm)
+            .filter(|cached| cached.fetched_at.elapsed() < max_age)
+            .and_then(|cached| cached.keys.find(kid))
+            .map(DecodingKey::from_jwk)
+            .transpose()
+            .map_err(|err| unauthorized(&format!("unusable signing key: {err}")))
+    }
+
+    async fn fetch_jwks(&self, realm: &str) -> Result<JwkSet, AppErr> {
+        let url = self.settings.jwks_url(realm);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|err| AppErr::from_owned(format!("get {url} failed with {err}")))
+            .await?;
+
+        let keys = response.ensure_success_json::<JwkSet>().await?;
+
+        log::info!("fetched {0} signing keys from {url}", keys.keys.len());
+        Ok(keys)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AuthUser {
+    pub id: String,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub roles: Vec<String>,
+    pub token: String,
+}
+
+impl AuthUser {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|x| x == role)
+    }
+
+    pub fn require_role(&self, role: &str) -> Result<(), HttpAppErr> {
+        if self.has_role(role) {
+            Ok(())
+        } else {
+            Err(HttpAppErr::new(
+                StatusCode::FORBIDDEN,
+                &format!("role {role} is required"),
+            ))
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = HttpAppErr;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let validator = parts
+            .extensions
+            .get::<Arc<JwtValidator>>()
+            .cloned()
+            .ok_or_else(|| {
+                HttpAppErr::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "token validator is not configured",
+                )
+            })?;
+
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| unauthorized("bearer token is missing"))?;
+
+        validator.validate(token).await
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    Single(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct ClientAccess {
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct IssuerClaims {
+    iss: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenClaims {
+    sub: String,
+    preferred_username: Option<String>,
+    email: Option<String>,
+    azp: Option<String>,
+    aud: Option<Audience>,
+    #[serde(default)]
+    resource_access: HashMap<String, ClientAccess>,
+}
+
+impl AccessTokenClaims {
+    fn is_issued_for(&self, client: &str) -> bool {
+        let in_audience = match &self.aud {
+            Some(Audience::Single(aud)) => aud == client,
+            Some(Audience::Many(aud)) => aud.iter().any(|x| x == client),
+            None => false,
+        };
+
+        in_audience || self.azp.as_deref() == Some(client)
+    }
+
+    fn into_user(mut self, client: &str, token: &str) -> AuthUser {
+        let roles = self
+            .resource_access
+            .remove(client)
+            .map(|access| access.roles)
+            .unwrap_or_default();
+
+        AuthUser {
+            id: self.sub,
+            username: self.preferred_username,
+            email: self.email,
+            roles,
+            token: token.to_owned(),
+        }
+    }
+}
+
+fn unauthorized(reason: &str) -> HttpAppErr {
+    HttpAppErr::new(StatusCode::UNAUTHORIZED, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use http::StatusCode;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use reqwest::Client;
+    use serde_json::json;
+
+    use crate::config::ConfigSource;
+
+    use super::{AccessTokenClaims, JwtValidator, JwtValidatorSettings};
+
+    fn validator() -> JwtValidator {
+        JwtValidator::new(
+            JwtValidatorSettings {
+                host: "http://127.0.0.1:1".to_owned(),
+                realms: vec!["store".to_owned(), "acme".to_owned()],
+                client: "app_client".to_owned(),
+            },
+            Arc::new(Client::new()),
+        )
+    }
+
+    fn claims(value: serde_json::Value) -> AccessTokenClaims {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_not_signed_with_rs256() {
+        let header = Header {
+            kid: Some("key-1".to_owned()),
+            ..Header::default()
+        };
+
+        let token = encode(
+            &header,
+            &json!({ "sub": "user-1", "iss": "http://127.0.0.1:1/realms/store", "exp": u32::MAX }),
+            &EncodingKey::from_secret(b"public-key-material"),
+        )
+        .unwrap();
+
+        let err = validator().validate(&token).await.err().unwrap();
+
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.reason, "unsupported signing algorithm");
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_without_key_id() {
+        let token = "eyJhbGciOiJSUzI1NiIsInR5cCI6IkpXVCJ9.e30.c2ln";
+
+        let err = validator().validate(token).await.err().unwrap();
+
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.reason, "token has no key id");
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_of_unconfigured_realm() {
+        // unsigned token issued by http://127.0.0.1:1/realms/globex
+        let token = "eyJhbGciOiJSUzI1NiIsImtpZCI6ImtleS0xIn0.eyJzdWIiOiJ1c2VyLTEiLCJpc3MiOiJodHRwOi8vMTI3LjAuMC4xOjEvcmVhbG1zL2dsb2JleCIsImV4cCI6NDI5NDk2NzI5NX0.c2ln";
+
+        let err = validator().validate(token).await.err().unwrap();
+
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.reason, "token was issued by an unknown realm");
+    }
+
+    #[tokio::test]
+    async fn fetches_keys_of_realm_that_issued_token() {
+        // unsigned token issued by http://127.0.0.1:1/realms/acme
+        let token = "eyJhbGciOiJSUzI1NiIsImtpZCI6ImtleS0xIn0.eyJzdWIiOiJ1c2VyLTEiLCJpc3MiOiJodHRwOi8vMTI3LjAuMC4xOjEvcmVhbG1zL2FjbWUiLCJleHAiOjQyOTQ5NjcyOTV9.c2ln";
+
+        let err = validator().validate(token).await.err().unwrap();
+
+        assert_eq!(err.status, StatusCode::FAILED_DEPENDENCY);
+        assert!(
+            err.reason
+                .contains("/realms/acme/protocol/openid-connect/certs")
+        );
+    }
+
+    #[test]
+    fn reads_realm_of_every_tenant() {
+        let values: HashMap<String, String> = [
+            ("KEYCLOAK_HOST", "http://keycloak:8080"),
+            ("KEYCLOAK_REALM", "store"),
+            ("KEYCLOAK_CLIENT", "app_client"),
+            ("TENANTS", "acme, globex-eu"),
+            ("TENANT_ACME_REALM", "acme_realm"),
+            ("TENANT_GLOBEX_EU_REALM", "globex_realm"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+
+        let settings = ConfigSource::new(values, HashMap::new())
+            .read::<JwtValidatorSettings>()
+            .ok()
+            .unwrap();
+
+        assert_eq!(settings.realms, ["store", "acme_realm", "globex_realm"]);
+        assert_eq!(
+            settings.realm_of("http://keycloak:8080/realms/globex_realm"),
+            Some("globex_realm")
+        );
+        assert_eq!(settings.realm_of("http://keycloak:8080/realms/other"), None);
+    }
+
+    #[test]
+    fn accepts_audience_or_authorized_party() {
+        let single = claims(json!({ "sub": "1", "aud": "app_client" }));
+        let many = claims(json!({ "sub": "1", "aud": ["account", "app_client"] }));
+        let azp = claims(json!({ "sub": "1", "aud": "account", "azp": "app_client" }));
+        let other = claims(json!({ "sub": "1", "aud": "account", "azp": "admin-cli" }));
+
+        assert!(single.is_issued_for("app_client"));
+        assert!(many.is_issued_for("app_client"));
+        assert!(azp.is_issued_for("app_client"));
+        assert!(!other.is_issued_for("app_client"));
+    }
+
+    #[test]
+    fn takes_roles_of_own_client_only() {
+        let user = claims(json!({
+            "sub": "user-1",
+            "email": "jane@example.com",
+            "resource_access": {
+                "app_client": { "roles": ["customer"] },
+                "account": { "roles": ["manage-account"] },
+            },
+        }))
+        .into_user("app_client", "token");
+
+        assert_eq!(user.id, "user-1");
+        assert_eq!(user.email.as_deref(), Some("jane@example.com"));
+        assert_eq!(user.roles, ["customer"]);
+        assert!(user.require_role("customer").is_ok());
+        assert_eq!(
+            user.require_role("vendor").err().unwrap().status,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
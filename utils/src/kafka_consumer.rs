@@ -1,29 +1,82 @@
+use std::time::Duration;
+
 use futures::StreamExt;
 use rdkafka::{
     ClientConfig, Message,
-    consumer::{Consumer, StreamConsumer},
+    consumer::{BaseConsumer, Consumer, StreamConsumer},
 };
 
-use crate::errors::AppErr;
+use crate::{config::ConfigReader, errors::AppErr};
+
+const OFFSET_RESETS: [&str; 2] = ["earliest", "latest"];
 
 pub trait KafkaTopic {
-    fn get_descriptor() -> Result<KafkaTopicDescriptor, AppErr>;
-    fn handle_message(payload: &[u8]) -> impl Future<Output = Result<(), AppErr>>;
+    fn get_descriptor(&self) -> KafkaTopicDescriptor;
+    fn handle_message(&self, payload: &[u8]) -> impl Future<Output = Result<(), AppErr>>;
 }
 
 pub struct KafkaTopicDescriptor {
     pub host: String,
     pub topic: String,
+    pub consumer: KafkaConsumerSettings,
+}
+
+// one group per service, shared by all topics the service consumes, so every service sees
+// every message of a topic other services consume as well
+#[derive(Clone)]
+pub struct KafkaConsumerSettings {
+    pub group: String,
+    // where a group starts on a topic it has no committed offset for: a new or renamed group
+    // replays the whole retained topic with earliest, latest skips what was published before
+    pub offset_reset: String,
+}
+
+impl KafkaConsumerSettings {
+    pub fn new(group: &str) -> Self {
+        KafkaConsumerSettings {
+            group: group.to_owned(),
+            offset_reset: OFFSET_RESETS[0].to_owned(),
+        }
+    }
+
+    pub fn read(reader: &mut ConfigReader, default_group: &str) -> Self {
+        let settings = KafkaConsumerSettings {
+            group: reader.parsed_or("KAFKA_CONSUMER_GROUP", default_group.to_owned()),
+            offset_reset: reader.parsed_or("KAFKA_OFFSET_RESET", OFFSET_RESETS[0].to_owned()),
+        };
+
+        if !OFFSET_RESETS.contains(&settings.offset_reset.as_str()) {
+            reader.error(&format!(
+                "KAFKA_OFFSET_RESET must be one of {0}",
+                OFFSET_RESETS.join(", ")
+            ));
+        }
+
+        settings
+    }
+}
+
+pub async fn ping_kafka(host: &str, timeout: Duration) -> Result<(), AppErr> {
+    let consumer: BaseConsumer = ClientConfig::new()
+        .set("bootstrap.servers", host)
+        .create()
+        .map_err(|err| AppErr::from_owned(format!("failed to create kafka client: {err}")))?;
+
+    tokio::task::spawn_blocking(move || consumer.fetch_metadata(None, timeout))
+        .await
+        .map_err(|err| AppErr::from_owned(format!("kafka ping failed: {err}")))?
+        .map(|metadata| log::debug!("kafka has {0} brokers", metadata.brokers().len()))
+        .map_err(|err| AppErr::from_owned(format!("kafka is unreachable: {err}")))
 }
 
-pub async fn consume_topic<Topic: KafkaTopic>() -> Result<(), AppErr> {
-    let descriptor = Topic::get_descriptor()?;
+pub async fn consume_topic<Topic: KafkaTopic>(topic: Topic) -> Result<(), AppErr> {
+    let descriptor = topic.get_descriptor();
 
     let consumer: StreamConsumer = ClientConfig::new()
         .set("bootstrap.servers", descriptor.host)
-        .set("group.id", "solution_group")
+        .set("group.id", descriptor.consumer.group)
         .set("enable.auto.commit", "true")
-        .set("auto.offset.reset", "earliest")
+        .set("auto.offset.reset", descriptor.consumer.offset_reset)
         .create()
         .map_err(|err| AppErr::from_owned(format!("failed to start consumer: {err}")))?;
 
@@ -35,7 +88,7 @@ pub async fn consume_topic<Topic: KafkaTopic>() -> Result<(), AppErr> {
         match message {
             Ok(m) => {
                 if let Some(payload) = m.payload() {
-                    match Topic::handle_message(payload).await {
+                    match topic.handle_message(payload).await {
                         Ok(_) => log::debug!("message handled"),
                         Err(err) => log::error!("failed message handling: {err}"),
                     }
@@ -1,6 +1,13 @@
 pub mod logging;
 pub mod errors;
 pub mod dotenv;
+pub mod config;
 pub mod env;
+pub mod health;
 pub mod http;
-pub mod kafka_consumer;
\ No newline at end of file
+pub mod kafka_consumer;
+pub mod jwt;
+pub mod readiness;
+pub mod validation;
+pub mod service_token;
+pub mod tenant;
\ No newline at end of file
@@ -0,0 +1,228 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use tokio::{
+    select,
+    time::{Instant, sleep},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    config::{ConfigReader, ServiceConfig},
+    errors::AppErr,
+};
+
+#[derive(Clone)]
+pub struct ReadinessSettings {
+    pub timeout: Duration,
+    pub attempt_timeout: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for ReadinessSettings {
+    fn default() -> Self {
+        ReadinessSettings {
+            timeout: Duration::from_secs(120),
+            attempt_timeout: Duration::from_secs(10),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ServiceConfig for ReadinessSettings {
+    fn read(reader: &mut ConfigReader) -> Self {
+        let defaults = ReadinessSettings::default();
+
+        ReadinessSettings {
+            timeout: Duration::from_secs(
+                reader.parsed_or("STARTUP_TIMEOUT_SECS", defaults.timeout.as_secs()),
+            ),
+            attempt_timeout: Duration::from_secs(reader.parsed_or(
+                "STARTUP_ATTEMPT_TIMEOUT_SECS",
+                defaults.attempt_timeout.as_secs(),
+            )),
+            initial_backoff: Duration::from_millis(reader.parsed_or(
+                "STARTUP_INITIAL_BACKOFF_MS",
+                defaults.initial_backoff.as_millis() as u64,
+            )),
+            max_backoff: Duration::from_millis(reader.parsed_or(
+                "STARTUP_MAX_BACKOFF_MS",
+                defaults.max_backoff.as_millis() as u64,
+            )),
+        }
+    }
+}
+
+impl ReadinessSettings {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        let jitter = RandomState::new().build_hasher().finish() % 1000;
+        exponential / 2 + exponential.mul_f64(jitter as f64 / 2000.0)
+    }
+}
+
+pub async fn wait_until_ready<TProbe, TFuture>(
+    name: &str,
+    settings: &ReadinessSettings,
+    cancellation_token: &CancellationToken,
+    mut probe: TProbe,
+) -> Result<(), AppErr>
+where
+    TProbe: FnMut(CancellationToken) -> TFuture,
+    TFuture: Future<Output = Result<(), AppErr>>,
+{
+    let deadline = Instant::now() + settings.timeout;
+    let mut attempt = 0;
+
+    loop {
+        log::info!("waiting for {name}, attempt {0}", attempt + 1);
+
+        let attempt_token = cancellation_token.child_token();
+        let attempt_timeout = settings
+            .attempt_timeout
+            .min(deadline.saturating_duration_since(Instant::now()));
+
+        let err = select! {
+            result = probe(attempt_token.clone()) => match result {
+                Ok(()) => {
+                    log::info!("{name} is ready");
+                    return Ok(());
+                }
+                Err(err) => err,
+            },
+            _ = sleep(attempt_timeout) => {
+                attempt_token.cancel();
+                AppErr::from_owned(format!("attempt timed out after {attempt_timeout:?}"))
+            },
+            _ = cancellation_token.cancelled() => return AppErr::cancelled(),
+        };
+
+        let backoff = settings.backoff(attempt);
+        if Instant::now() + backoff >= deadline {
+            return Err(AppErr::from_owned(format!(
+                "{name} is not ready after {0:?}: {err}",
+                settings.timeout
+            )));
+        }
+
+        log::warn!("{name} is not ready, retry in {backoff:?}: {err}");
+        attempt += 1;
+
+        select! {
+            _ = sleep(backoff) => {},
+            _ = cancellation_token.cancelled() => return AppErr::cancelled(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Mutex,
+            atomic::{AtomicU32, Ordering},
+        },
+        time::Duration,
+    };
+
+    use tokio_util::sync::CancellationToken;
+
+    use crate::errors::AppErr;
+
+    use super::{ReadinessSettings, wait_until_ready};
+
+    fn fast_settings() -> ReadinessSettings {
+        ReadinessSettings {
+            timeout: Duration::from_millis(500),
+            attempt_timeout: Duration::from_millis(50),
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(20),
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_until_probe_succeeds() {
+        let attempts = AtomicU32::new(0);
+
+        let result = wait_until_ready(
+            "service",
+            &fast_settings(),
+            &CancellationToken::new(),
+            |_| async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0..=2 => Err(AppErr::from("not yet")),
+                    _ => Ok(()),
+                }
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn cancels_hung_attempts_and_gives_up_after_timeout() {
+        let attempt_tokens = Mutex::new(vec![]);
+
+        let result = wait_until_ready(
+            "service",
+            &fast_settings(),
+            &CancellationToken::new(),
+            |token| {
+                attempt_tokens.lock().unwrap().push(token);
+                std::future::pending()
+            },
+        )
+        .await;
+
+        let err = result.err().unwrap().to_string();
+        assert!(err.contains("service is not ready"));
+        assert!(err.contains("timed out"));
+
+        let attempt_tokens = attempt_tokens.lock().unwrap();
+        assert!(attempt_tokens.len() > 1);
+        assert!(attempt_tokens.iter().all(CancellationToken::is_cancelled));
+    }
+
+    #[tokio::test]
+    async fn stops_when_cancelled() {
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+
+        let result = wait_until_ready(
+            "service",
+            &fast_settings(),
+            &cancellation_token,
+            |token| async move {
+                token.cancelled().await;
+                Err(AppErr::from("cancelled"))
+            },
+        )
+        .await;
+
+        assert_eq!(result.err().unwrap().to_string(), "op cancelled");
+    }
+
+    #[test]
+    fn backoff_grows_with_jitter_up_to_max() {
+        let settings = fast_settings();
+
+        for attempt in 0..10 {
+            let backoff = settings.backoff(attempt);
+            let ceiling = Duration::from_millis(5 * 2u64.pow(attempt)).min(settings.max_backoff);
+
+            assert!(backoff >= ceiling / 2);
+            assert!(backoff <= ceiling);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use reqwest::{Client, Response};
+use serde::Deserialize;
+use tokio::{select, sync::Mutex};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    config::{ConfigReader, ServiceConfig},
+    errors::AppErr,
+    http::ResponseExtended,
+};
+
+const EXPIRATION_MARGIN: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct ClientCredentialsSettings {
+    pub host: String,
+    pub realm: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl ServiceConfig for ClientCredentialsSettings {
+    fn read(reader: &mut ConfigReader) -> Self {
+        ClientCredentialsSettings {
+            host: reader.required("KEYCLOAK_HOST"),
+            realm: reader.required("KEYCLOAK_REALM"),
+            client_id: reader.required("SERVICE_CLIENT_ID"),
+            client_secret: reader.required("SERVICE_CLIENT_SECRET"),
+        }
+    }
+}
+
+impl ClientCredentialsSettings {
+    fn token_url(&self) -> String {
+        format!(
+            "{0}/realms/{1}/protocol/openid-connect/token",
+            self.host, self.realm
+        )
+    }
+}
+
+pub trait ServiceTokenSource {
+    fn get_token(&self) -> impl Future<Output = Result<String, AppErr>> + Send;
+    fn get_token_with_cancel(
+        &self,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Result<String, AppErr>> + Send;
+}
+
+#[derive(Deserialize)]
+struct ClientCredentialsResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+struct CachedServiceToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+impl CachedServiceToken {
+    fn is_fresh(&self) -> bool {
+        Instant::now() + EXPIRATION_MARGIN < self.expires_at
+    }
+}
+
+pub struct ClientCredentialsTokenSource {
+    settings: ClientCredentialsSettings,
+    client: Arc<Client>,
+    cache: Mutex<Option<CachedServiceToken>>,
+}
+
+impl ClientCredentialsTokenSource {
+    pub fn new(settings: ClientCredentialsSettings, client: Arc<Client>) -> Self {
+        ClientCredentialsTokenSource {
+            settings,
+            client,
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn request_token(
+        &self,
+        cancellation_token: &CancellationToken,
+    ) -> Result<ClientCredentialsResponse, AppErr> {
+        let form_data = [
+            ("grant_type", "client_credentials"),
+            ("client_id", &self.settings.client_id),
+            ("client_secret", &self.settings.client_secret),
+        ];
+
+        let response = select! {
+            resp = self.client
+            .post(self.settings.token_url())
+            .form(&form_data)
+            .send() => resp.map_err(|err| AppErr::from_owned(format!("service auth call err: {err}"))),
+            _ = cancellation_token.cancelled() => Result::<Response, AppErr>::Err(AppErr::from("service auth call cancelled"))
+        }?;
+
+        let token = response
+            .ensure_success_json::<ClientCredentialsResponse>()
+            .await?;
+        Ok(token)
+    }
+}
+
+impl ServiceTokenSource for ClientCredentialsTokenSource {
+    async fn get_token_with_cancel(
+        &self,
+        cancellation_token: &CancellationToken,
+    ) -> Result<String, AppErr> {
+        let mut cache = select! {
+            guard = self.cache.lock() => Ok(guard),
+            _ = cancellation_token.cancelled() => Err(AppErr::from("service auth call cancelled"))
+        }?;
+
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.is_fresh()) {
+            return Ok(cached.access_token.clone());
+        }
+
+        let token = self.request_token(cancellation_token).await?;
+        log::info!("service token for {0} acquired", self.settings.client_id);
+
+        *cache = Some(CachedServiceToken {
+            access_token: token.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+        });
+
+        Ok(token.access_token)
+    }
+
+    async fn get_token(&self) -> Result<String, AppErr> {
+        self.get_token_with_cancel(&CancellationToken::new()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
+    };
+
+    use axum::{
+        Form, Json, Router,
+        extract::State,
+        routing::{get, post},
+    };
+    use http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+    use serde_json::{Value, json};
+    use tokio::net::TcpListener;
+
+    use crate::http::{HttpClientSettings, SendExtended, create_http_client};
+
+    use super::{ClientCredentialsSettings, ClientCredentialsTokenSource, ServiceTokenSource};
+
+    async fn start_token_server(lifetime: u64) -> (String, Arc<AtomicU64>) {
+        let issued = Arc::new(AtomicU64::new(0));
+
+        let router = Router::new()
+            .route(
+                "/realms/store/protocol/openid-connect/token",
+                post(
+                    move |State(issued): State<Arc<AtomicU64>>,
+                          Form(form): Form<HashMap<String, String>>| async move {
+                        let valid = form["grant_type"] == "client_credentials"
+                            && form["client_id"] == "goods_service"
+                            && form["client_secret"] == "s3cret";
+
+                        if !valid {
+                            return (StatusCode::UNAUTHORIZED, Json(json!({})));
+                        }
+
+                        let number = issued.fetch_add(1, Ordering::SeqCst);
+                        let token: Value = json!({
+                            "access_token": format!("token-{number}"),
+                            "expires_in": lifetime,
+                        });
+                        (StatusCode::OK, Json(token))
+                    },
+                ),
+            )
+            .route(
+                "/api/protected",
+                get(|headers: HeaderMap| async move {
+                    match headers.get(AUTHORIZATION) {
+                        Some(value) if value == "Bearer token-0" => StatusCode::OK,
+                        _ => StatusCode::UNAUTHORIZED,
+                    }
+                }),
+            )
+            .with_state(issued.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("http://{0}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        (host, issued)
+    }
+
+    fn token_source(host: &str, secret: &str) -> ClientCredentialsTokenSource {
+        ClientCredentialsTokenSource::new(
+            ClientCredentialsSettings {
+                host: host.to_owned(),
+                realm: "store".to_owned(),
+                client_id: "goods_service".to_owned(),
+                client_secret: secret.to_owned(),
+            },
+            Arc::new(create_http_client(&HttpClientSettings::new("service-token-tests")).unwrap()),
+        )
+    }
+
+    #[tokio::test]
+    async fn reuses_cached_token_on_second_call() {
+        let (host, issued) = start_token_server(300).await;
+        let source = token_source(&host, "s3cret");
+
+        assert_eq!(source.get_token().await.unwrap(), "token-0");
+        assert_eq!(source.get_token().await.unwrap(), "token-0");
+        assert_eq!(issued.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn requests_new_token_once_cached_one_expires() {
+        let (host, issued) = start_token_server(0).await;
+        let source = token_source(&host, "s3cret");
+
+        assert_eq!(source.get_token().await.unwrap(), "token-0");
+        assert_eq!(source.get_token().await.unwrap(), "token-1");
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn requests_new_token_when_cached_one_is_about_to_expire() {
+        let (host, issued) = start_token_server(10).await;
+        let source = token_source(&host, "s3cret");
+
+        assert_eq!(source.get_token().await.unwrap(), "token-0");
+        assert_eq!(source.get_token().await.unwrap(), "token-1");
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn attaches_token_through_send_extended() {
+        let (host, _) = start_token_server(300).await;
+        let source = token_source(&host, "s3cret");
+        let client = create_http_client(&HttpClientSettings::new("service-token-tests")).unwrap();
+        let url = format!("{host}/api/protected");
+
+        let token = source.get_token().await.unwrap();
+        let response = client.quick_get(url.as_str(), Some(&token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client.quick_get(url.as_str(), None::<&str>).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn reports_rejected_credentials() {
+        let (host, issued) = start_token_server(300).await;
+        let source = token_source(&host, "wrong");
+
+        assert!(source.get_token().await.is_err());
+        assert_eq!(issued.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+};
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+
+use crate::errors::HttpAppErr;
+
+const EMAIL_MAX_LENGTH: usize = 254;
+const PASSWORD_MIN_LENGTH: usize = 8;
+const PASSWORD_MAX_LENGTH: usize = 128;
+
+pub trait Validate {
+    fn validate(&self, validator: &mut Validator);
+}
+
+#[derive(Default)]
+pub struct Validator {
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    pub fn required(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.error(field, "is required");
+        }
+        self
+    }
+
+    pub fn length(&mut self, field: &str, value: &str, min: usize, max: usize) -> &mut Self {
+        let length = value.chars().count();
+
+        if !value.is_empty() && length < min {
+            self.error(field, &format!("must be at least {min} characters long"));
+        }
+        if length > max {
+            self.error(field, &format!("must be at most {max} characters long"));
+        }
+        self
+    }
+
+    pub fn email(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.is_empty() {
+            return self;
+        }
+
+        let valid = value.len() <= EMAIL_MAX_LENGTH
+            && !value.chars().any(char::is_whitespace)
+            && value.split_once('@').is_some_and(|(local, domain)| {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain
+                        .split('.')
+                        .all(|label| !label.is_empty() && !label.starts_with('-'))
+                    && domain.contains('.')
+            });
+
+        if !valid {
+            self.error(field, "must be a valid email address");
+        }
+        self
+    }
+
+    pub fn password(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.is_empty() {
+            return self;
+        }
+
+        self.length(field, value, PASSWORD_MIN_LENGTH, PASSWORD_MAX_LENGTH);
+
+        if !value.chars().any(char::is_alphabetic) {
+            self.error(field, "must contain a letter");
+        }
+        if !value.chars().any(|x| x.is_ascii_digit()) {
+            self.error(field, "must contain a digit");
+        }
+        self
+    }
+
+    pub fn error(&mut self, field: &str, message: &str) -> &mut Self {
+        self.errors
+            .entry(field.to_owned())
+            .or_default()
+            .push(message.to_owned());
+        self
+    }
+
+    pub fn finish(self) -> Result<(), HttpAppErr> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(HttpAppErr::validation(self.errors))
+        }
+    }
+}
+
+pub struct ValidJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = HttpAppErr;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) =
+            Json::<T>::from_request(request, state)
+                .await
+                .map_err(|rejection: JsonRejection| {
+                    HttpAppErr::new(StatusCode::BAD_REQUEST, &rejection.body_text())
+                })?;
+
+        let mut validator = Validator::new();
+        value.validate(&mut validator);
+        validator.finish()?;
+
+        Ok(ValidJson(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Validator;
+
+    fn errors(validate: impl FnOnce(&mut Validator)) -> Vec<String> {
+        let mut validator = Validator::new();
+        validate(&mut validator);
+
+        match validator.finish() {
+            Ok(()) => vec![],
+            Err(err) => err.errors.into_values().flatten().collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_emails() {
+        for email in ["jane@example.com", "jane.doe+shop@mail.example.org"] {
+            assert!(
+                errors(|x| {
+                    x.email("email", email);
+                })
+                .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_emails() {
+        for email in [
+            "jane",
+            "jane@",
+            "@example.com",
+            "jane@example",
+            "ja ne@example.com",
+        ] {
+            assert_eq!(
+                errors(|x| {
+                    x.email("email", email);
+                }),
+                ["must be a valid email address"],
+                "{email}"
+            );
+        }
+    }
+
+    #[test]
+    fn enforces_password_policy() {
+        assert_eq!(
+            errors(|x| {
+                x.password("password", "short");
+            }),
+            ["must be at least 8 characters long", "must contain a digit"]
+        );
+        assert!(
+            errors(|x| {
+                x.password("password", "Secret123");
+            })
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn reports_required_fields_once() {
+        assert_eq!(
+            errors(|x| {
+                x.required("email", "")
+                    .email("email", "")
+                    .password("email", "");
+            }),
+            ["is required"]
+        );
+    }
+}
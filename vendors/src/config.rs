@@ -0,0 +1,32 @@
+use utils::{
+    config::{ConfigReader, ServiceConfig},
+    jwt::JwtValidatorSettings,
+    kafka_consumer::KafkaConsumerSettings,
+    readiness::ReadinessSettings,
+};
+
+pub struct VendorsConfig {
+    pub service_host: String,
+    pub db_host: String,
+    pub kafka_host: String,
+    pub vendor_topic: String,
+    pub user_deleted_topic: String,
+    pub kafka_consumer: KafkaConsumerSettings,
+    pub jwt: JwtValidatorSettings,
+    pub startup: ReadinessSettings,
+}
+
+impl ServiceConfig for VendorsConfig {
+    fn read(reader: &mut ConfigReader) -> Self {
+        VendorsConfig {
+            service_host: reader.required("SERVICE_HOST"),
+            db_host: reader.required("DB_HOST"),
+            kafka_host: reader.required("KAFKA_HOST"),
+            vendor_topic: reader.required("KAFKA_VENDOR_TOPIC"),
+            user_deleted_topic: reader.required("KAFKA_USER_DELETED_TOPIC"),
+            kafka_consumer: KafkaConsumerSettings::read(reader, "vendors_group"),
+            jwt: JwtValidatorSettings::read(reader),
+            startup: ReadinessSettings::read(reader),
+        }
+    }
+}
@@ -1,46 +1,76 @@
+use std::sync::Arc;
+
 use futures::TryFutureExt;
-use sea_orm::{ActiveModelTrait, ActiveValue::Set};
+use sea_orm::{ActiveValue::Set, EntityTrait, sea_query::OnConflict};
 use serde::Deserialize;
 use utils::{
-    env::env_var,
     errors::AppErr,
     kafka_consumer::{KafkaTopic, KafkaTopicDescriptor},
+    tenant::default_tenant,
 };
 
-use crate::db_factory::get_db_conn;
+use crate::{
+    config::VendorsConfig,
+    db_factory::get_db_conn,
+    entity::vendor::{ActiveModel, Column, Entity},
+};
 
-pub struct CreateVendorTopic;
-impl KafkaTopic for CreateVendorTopic {
-    fn get_descriptor() -> Result<utils::kafka_consumer::KafkaTopicDescriptor, AppErr> {
-        let descriptor = KafkaTopicDescriptor {
-            host: env_var("KAFKA_HOST")?,
-            topic: env_var("KAFKA_VENDOR_TOPIC")?,
-        };
+pub struct CreateVendorTopic {
+    config: Arc<VendorsConfig>,
+}
 
-        Ok(descriptor)
+impl CreateVendorTopic {
+    pub fn new(config: Arc<VendorsConfig>) -> Self {
+        CreateVendorTopic { config }
+    }
+}
+
+impl KafkaTopic for CreateVendorTopic {
+    fn get_descriptor(&self) -> KafkaTopicDescriptor {
+        KafkaTopicDescriptor {
+            host: self.config.kafka_host.clone(),
+            topic: self.config.vendor_topic.clone(),
+            consumer: self.config.kafka_consumer.clone(),
+        }
     }
 
-    async fn handle_message(payload: &[u8]) -> Result<(), AppErr> {
+    async fn handle_message(&self, payload: &[u8]) -> Result<(), AppErr> {
         let event = serde_json::from_slice::<CreateVendorEvent>(payload)
             .map_err(|err| AppErr::from_owned(format!("failed at serialization: {err}")))?;
 
-        let customer = crate::entity::vendor::ActiveModel {
-            email: Set(event.email),
+        let vendor = ActiveModel {
+            tenant: Set(event.tenant.clone()),
+            email: Set(event.email.clone()),
             ..Default::default()
         };
 
-        let db = get_db_conn().await?;
-        customer
-            .insert(&db)
+        let db = get_db_conn(&self.config.db_host).await?;
+        let inserted = Entity::insert(vendor)
+            .on_conflict(
+                OnConflict::columns([Column::Tenant, Column::Email])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&db)
             .map_err(|err| AppErr::from_owned(format!("failed to create vendor: {err}")))
             .await?;
 
-        log::info!("new vendor created");
+        if inserted == 0 {
+            log::info!(
+                "vendor {0} already exists in tenant {1}",
+                event.email,
+                event.tenant
+            );
+        } else {
+            log::info!("new vendor created");
+        }
         Ok(())
     }
 }
 
 #[derive(Deserialize)]
 struct CreateVendorEvent {
+    #[serde(default = "default_tenant")]
+    pub tenant: String,
     pub email: String,
 }
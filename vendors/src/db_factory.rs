@@ -2,10 +2,10 @@ use std::time::Duration;
 
 use futures::TryFutureExt;
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
-use utils::{env::env_var, errors::AppErr};
+use utils::errors::AppErr;
 
-pub async fn get_db_conn() -> Result<DatabaseConnection, AppErr> {
-    let mut opt = ConnectOptions::new(env_var("DB_HOST")?);
+pub async fn get_db_conn(db_host: &str) -> Result<DatabaseConnection, AppErr> {
+    let mut opt = ConnectOptions::new(db_host);
     opt.max_connections(100)
         .min_connections(1)
         .connect_timeout(Duration::from_secs(8))
@@ -1,17 +1,31 @@
+pub mod config;
 pub mod create_vendor_topic;
 pub mod db_factory;
+pub mod delete_user_topic;
 pub mod entity;
 pub mod migrations;
 
+use std::sync::Arc;
+
+use axum::{Extension, Router};
+use config::VendorsConfig;
 use create_vendor_topic::CreateVendorTopic;
 use db_factory::get_db_conn;
+use delete_user_topic::DeleteUserTopic;
 use futures::TryFutureExt;
+use sea_orm::DatabaseConnection;
 use sea_orm_migration::MigratorTrait;
+use tokio_util::sync::CancellationToken;
 use utils::{
+    config::load_config,
     dotenv::configure_dotenv,
     errors::AppErr,
+    health::{HealthChecks, create_health_router},
+    http::{HttpClientSettings, create_http_client},
+    jwt::JwtValidator,
     kafka_consumer::{self},
     logging::configure_logs,
+    readiness::wait_until_ready,
 };
 
 #[tokio::main]
@@ -19,12 +33,78 @@ async fn main() -> Result<(), AppErr> {
     configure_dotenv();
     _ = configure_logs(log::LevelFilter::Debug)?;
 
-    let db = get_db_conn().await?;
+    let config = Arc::new(load_config::<VendorsConfig>()?);
+
+    let startup_cancellation = CancellationToken::new();
+    let startup_cancellation_clone = startup_cancellation.clone();
+
+    tokio::task::spawn(async move {
+        _ = tokio::signal::ctrl_c().await;
+        startup_cancellation_clone.cancel();
+    });
+
+    let db = get_db_conn(&config.db_host).await?;
+
+    wait_until_ready("postgres", &config.startup, &startup_cancellation, |_| {
+        db.ping()
+            .map_err(|err| AppErr::from_owned(format!("postgres is unreachable: {err}")))
+    })
+    .await?;
+
+    wait_until_ready("kafka", &config.startup, &startup_cancellation, |_| {
+        kafka_consumer::ping_kafka(&config.kafka_host, config.startup.attempt_timeout)
+    })
+    .await?;
+
     migrations::migrator::Migrator::up(&db, None)
         .map_err(|err| AppErr::from_owned(format!("failed to migrate database: {err}")))
         .await?;
 
-    kafka_consumer::consume_topic::<CreateVendorTopic>().await?;
+    let validator = Arc::new(JwtValidator::new(
+        config.jwt.clone(),
+        Arc::new(create_http_client(&HttpClientSettings::new(
+            "web3store-vendors",
+        ))?),
+    ));
+
+    let app = Router::new()
+        .merge(create_health_router(create_health_checks(
+            db.clone(),
+            config.clone(),
+        )))
+        .layer(Extension(validator));
+
+    let listener = tokio::net::TcpListener::bind(&config.service_host)
+        .map_err(|err| AppErr::from_owned(format!("failed to bind: {err}")))
+        .await?;
+
+    log::info!("app started at: {0}", config.service_host);
+
+    tokio::try_join!(
+        kafka_consumer::consume_topic(CreateVendorTopic::new(config.clone())),
+        kafka_consumer::consume_topic(DeleteUserTopic::new(db.clone(), config.clone())),
+        axum::serve(listener, app)
+            .into_future()
+            .map_err(|err| AppErr::from_owned(format!("server failed {err}")))
+    )?;
 
     Ok(())
 }
+
+fn create_health_checks(db: DatabaseConnection, config: Arc<VendorsConfig>) -> HealthChecks {
+    HealthChecks::new()
+        .with_check("postgres", move || {
+            let db = db.clone();
+            async move {
+                db.ping()
+                    .map_err(|err| AppErr::from_owned(format!("postgres is unreachable: {err}")))
+                    .await
+            }
+        })
+        .with_check("kafka", move || {
+            let config = config.clone();
+            async move {
+                kafka_consumer::ping_kafka(&config.kafka_host, config.startup.attempt_timeout).await
+            }
+        })
+}
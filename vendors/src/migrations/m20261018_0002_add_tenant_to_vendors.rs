@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(Iden)]
+enum Vendors {
+    Table,
+    Tenant,
+    Email,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Vendors::Table)
+                    .add_column(
+                        ColumnDef::new(Vendors::Tenant)
+                            .string()
+                            .not_null()
+                            .char_len(64)
+                            .default("default"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("ALTER TABLE vendors DROP CONSTRAINT IF EXISTS vendors_email_key")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_vendors_tenant_email")
+                    .table(Vendors::Table)
+                    .col(Vendors::Tenant)
+                    .col(Vendors::Email)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_vendors_tenant_email")
+                    .table(Vendors::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE vendors ADD CONSTRAINT vendors_email_key UNIQUE (email)",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Vendors::Table)
+                    .drop_column(Vendors::Tenant)
+                    .to_owned(),
+            )
+            .await
+    }
+}